@@ -0,0 +1,49 @@
+// times vote registration over a large electorate, where checks against the
+// electorate and against previous votes must not be linear. run with
+// `cargo run --release --example bench_referendum`
+
+use vote::{Procedure, Person, PersonList, Motion, MockClock};
+
+use chrono::{Duration, Utc};
+
+use std::time::Instant;
+
+const POPULATION_SIZE: u64 = 100_000;
+
+type Result<T> = std::result::Result<T, ()>;
+
+fn main() -> Result<()> {
+    let persons: PersonList = (0..POPULATION_SIZE)
+        .map(|i| Person { name: format!("person {i}"), external_id: None })
+        .collect();
+    let electors: Vec<_> = persons.ids().collect();
+
+    let motion = Motion::new("benchmark", "", Vec::new(), electors.clone())
+        .map_err(|_| ())?;
+    let clock = MockClock::new(Utc::now());
+
+    let start = Instant::now();
+    let prototype = Procedure::begin_with_clock(motion, clock.clone());
+    let proposal = prototype.into_proposal(Duration::weeks(1)).map_err(|_| ())?;
+    clock.advance(Duration::weeks(1));
+    let mut petition = proposal.into_petition(1.0).map_err(|_| ())?;
+    println!("began and sampled the petition in {:?}", start.elapsed());
+
+    let start = Instant::now();
+    for &id in &electors {
+        petition.register_approval_vote(id).map_err(|_| ())?;
+    }
+    println!("registered {POPULATION_SIZE} approvals in {:?}", start.elapsed());
+
+    let start = Instant::now();
+    let mut referendum = petition.into_referendum(Duration::days(1)).map_err(|_| ())?;
+    println!("opened the referendum in {:?}", start.elapsed());
+
+    let start = Instant::now();
+    for &id in &electors {
+        referendum.register_vote_for(id).map_err(|_| ())?;
+    }
+    println!("registered {POPULATION_SIZE} votes in {:?}", start.elapsed());
+
+    Ok(())
+}
@@ -1,5 +1,8 @@
+// newlines are written explicitly to mirror the layout of the output
+#![allow(clippy::print_with_newline)]
+
 use vote::{Procedure, Person, PersonList, Motion};
-use vote::procedure::{Prototype, Proposal, Petition, Referendum};
+use vote::procedure::{Prototype, Proposal, Petition, Referendum, PETITIONER_RATIO};
 
 use rand::Rng;
 
@@ -8,7 +11,9 @@ use chrono::Duration;
 const POPULATION_SIZE: u64 = 21;
 const DEVELOPER_COUNT: u64 = 4;
 const VOTE_CHANCE: f64 = 0.8;
+const PROPOSAL_VOTE_SECS: i64 = 60;
 const PROPOSAL_SECS: i64 = 4;
+const REFERENDUM_SECS: i64 = 120;
 
 type Result<T> = std::result::Result<T, ()>;
 
@@ -20,7 +25,7 @@ fn main() -> Result<()> {
 
     let prototype = build_prototype(motion);
     let proposal = build_proposal(prototype, &persons)?;
-    let petition = build_petition(proposal);
+    let petition = build_petition(proposal)?;
     let referendum = build_referendum(petition, &persons)?;
 
     pass_motion(referendum, &persons)
@@ -32,7 +37,8 @@ fn build_population() -> PersonList {
     let rng = RNG::from(&Language::Roman);
 
     let persons = (0..POPULATION_SIZE).map(|_| Person {
-        name: rng.generate_short() + " " + &rng.generate_name()
+        name: rng.generate_short() + " " + &rng.generate_name(),
+        external_id: None
     }).collect();
 
     print!("--- The population of Exampletown ({POPULATION_SIZE})\n\n");
@@ -43,15 +49,15 @@ fn build_population() -> PersonList {
 }
 
 fn build_motion(persons: &PersonList) -> Motion {
-    let motion = Motion {
-        title: "Construction of a new monument in Exampletown",
-        description: "Exampletown is too empty. A monument must be built.",
-        developers: persons.rand_choices(DEVELOPER_COUNT).into(),
-        electors: persons.ids().collect()
-    };
+    let motion = Motion::new(
+        "Construction of a new monument in Exampletown",
+        "Exampletown is too empty. A monument must be built.",
+        persons.rand_choices(DEVELOPER_COUNT).unwrap(),
+        persons.ids().collect()
+    ).expect("developers are distinct members of the population");
 
     print!("--- The motion\n\n");
-    print!("{motion}\n\n");
+    print!("{}\n\n", motion.summary());
     pause_long();
 
     print!("--- The developers of the motion ({DEVELOPER_COUNT})\n\n");
@@ -81,11 +87,13 @@ fn build_proposal(
 
     print!(
         "{} votes for proposal required. Voters:\n\n",
-        prototype.motion().dev_count() / 2 + 1
+        prototype.votes_needed()
     );
 
     pause_short();
 
+    prototype.start_proposal_attempt(Duration::seconds(PROPOSAL_VOTE_SECS));
+
     for idx in 0..prototype.motion().dev_count() {
         let dev_id = prototype.motion().developers[idx];
 
@@ -93,14 +101,15 @@ fn build_proposal(
         pause_micro();
 
         if rng.gen_bool(VOTE_CHANCE) {
-            prototype.register_proposal_vote(dev_id).unwrap();
+            prototype.register_proposal_vote(dev_id)
+                .unwrap_or_else(|e| println!("Vote rejected: {e}"));
         }
     }
 
     print!("\n{} votes registered for proposal.\n\n", prototype.proposal_votes());
     pause_short();
 
-    let proposal = prototype.into_proposal(Duration::seconds(PROPOSAL_SECS))
+    let proposal = prototype.into_proposal_unchecked(Duration::seconds(PROPOSAL_SECS))
         .map_err(|_| println!("Insufficient votes for proposal"))?;
 
     print!("--- Stage 2: Proposal\n");
@@ -111,25 +120,19 @@ fn build_proposal(
     Ok(proposal)
 }
 
-fn build_petition(mut proposal: Procedure<Proposal>) -> Procedure<Petition> {
-    let petition = loop {
-        match proposal.into_petition() {
-            Ok(pet) => {
-                print!("Proposal stage end date reached.\n\n");
-                pause_short();
-                break pet;
-            }
+fn build_petition(proposal: Procedure<Proposal>) -> Result<Procedure<Petition>> {
+    let petition = proposal.wait_and_into_petition(PETITIONER_RATIO)
+        .map_err(|_| println!("Nobody to petition"))?;
 
-            Err(pro) => proposal = pro
-        }
-    };
+    print!("Proposal stage end date reached.\n\n");
+    pause_short();
 
     print!("--- Stage 3: Petition\n");
     print!("--- The motion is subject to a vote of approval by a subset of the population.\n");
     print!("--- If approved, the motion is shown to the population for a general vote.\n\n");
     pause_long();
 
-    petition
+    Ok(petition)
 }
 
 fn build_referendum(
@@ -141,7 +144,7 @@ fn build_referendum(
 
     print!(
         "{} votes for referendum required. Voters:\n\n",
-        voter_ids.len() / 2 + 1
+        petition.votes_needed()
     );
 
     pause_short();
@@ -151,14 +154,15 @@ fn build_referendum(
         pause_micro();
 
         if rng.gen_bool(VOTE_CHANCE) {
-            petition.register_approval_vote(id).unwrap();
+            petition.register_approval_vote(id)
+                .unwrap_or_else(|e| println!("Vote rejected: {e}"));
         }
     }
 
     print!("\n{} votes registered for referendum.\n\n", petition.votes_for());
     pause_short();
 
-    let referendum = petition.into_referendum()
+    let referendum = petition.into_referendum(Duration::seconds(REFERENDUM_SECS))
         .map_err(|_| println!("Insufficient votes for referendum"))?;
 
     print!("--- Stage 4: Referendum\n");
@@ -183,9 +187,11 @@ fn pass_motion(
         pause_micro();
 
         if rng.gen_bool(VOTE_CHANCE) {
-            referendum.register_vote_for(id).unwrap();
+            referendum.register_vote_for(id)
+                .unwrap_or_else(|e| println!("Vote rejected: {e}"));
         } else {
-            referendum.register_vote_against(id).unwrap();
+            referendum.register_vote_against(id)
+                .unwrap_or_else(|e| println!("Vote rejected: {e}"));
         }
     }
 
@@ -193,7 +199,7 @@ fn pass_motion(
     print!("{} votes registered against.\n\n", referendum.votes_against());
     pause_short();
 
-    if let Ok(()) = referendum.pass() {
+    if referendum.pass().is_ok() {
         print!("--- The motion is passed.\n");
         Ok(())
     } else {
@@ -0,0 +1,112 @@
+//! non-binding polls of a population, to inform the development of motions
+//!
+//! polls have no bearing on the electoral procedure, see
+//! [`Procedure`](crate::Procedure)
+
+pub mod error;
+
+pub use error::PollError;
+
+use crate::{PersonList, PersonId, SampleError};
+
+use rand::Rng;
+
+use std::collections::HashMap;
+
+/// z-score of the 95% confidence level
+const Z_95: f32 = 1.96;
+
+/// an approve/disapprove poll of a random sample of a population
+#[derive(Debug)]
+pub struct Poll {
+    respondents: Vec<PersonId>,
+    /// whether each respondent who responded approves
+    responses: HashMap<PersonId, bool>
+}
+
+/// results of a poll
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PollSummary {
+    pub sample_size: u64,
+    pub responses: u64,
+    pub approvals: u64,
+    /// fraction of responses approving, from 0 to 1
+    pub approval: f32,
+    /// half-width of the 95% confidence interval of `approval`, by normal
+    /// approximation. rough for small or lopsided samples
+    pub margin_of_error: f32
+}
+
+impl Poll {
+    /// a poll of `n` people drawn at random from `people`
+    ///
+    /// error if n > the number of people in the list
+    pub fn sample(people: &PersonList, n: u64) -> Result<Self, SampleError> {
+        Self::sample_with(people, n, &mut rand::thread_rng())
+    }
+
+    /// like [`sample`](Self::sample), drawing from `rng`
+    pub fn sample_with(
+        people: &PersonList,
+        n: u64,
+        rng: &mut impl Rng
+    ) -> Result<Self, SampleError> {
+        let respondents = people.rand_choices_with(rng, n)?;
+
+        Ok(Self { respondents, responses: HashMap::new() })
+    }
+
+    /// people sampled for the poll, whether they have responded or not
+    pub fn respondents(&self) -> &[PersonId] {
+        &self.respondents
+    }
+
+    /// error and does nothing if `person_id` was not sampled or has already
+    /// responded
+    pub fn respond(&mut self, person_id: PersonId, approve: bool) -> Result<(), PollError> {
+        if !self.respondents.contains(&person_id) {
+            return Err(PollError::NotRespondent(person_id));
+        }
+
+        if self.responses.contains_key(&person_id) {
+            return Err(PollError::AlreadyResponded(person_id));
+        }
+
+        self.responses.insert(person_id, approve);
+
+        Ok(())
+    }
+
+    /// approval and margin of error are 0 if nobody has responded
+    pub fn summary(&self) -> PollSummary {
+        let responses = self.responses.len() as u64;
+        let approvals = self.responses.values().filter(|&&approve| approve).count() as u64;
+
+        let (approval, margin_of_error) = match responses {
+            0 => (0.0, 0.0),
+            n => {
+                let p = approvals as f32 / n as f32;
+
+                (p, Z_95 * (p * (1.0 - p) / n as f32).sqrt())
+            }
+        };
+
+        PollSummary {
+            sample_size: self.respondents.len() as u64,
+            responses,
+            approvals,
+            approval,
+            margin_of_error
+        }
+    }
+}
+
+impl PollSummary {
+    /// bounds of the 95% confidence interval of the approval, within [0, 1]
+    pub fn confidence_interval(&self) -> (f32, f32) {
+        (
+            (self.approval - self.margin_of_error).max(0.0),
+            (self.approval + self.margin_of_error).min(1.0)
+        )
+    }
+}
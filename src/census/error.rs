@@ -0,0 +1,25 @@
+//! errors arising from polls
+
+use crate::PersonId;
+
+use std::{error::Error, fmt};
+
+/// reason for which a poll response was rejected, naming the respondent
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PollError {
+    /// the person was not sampled for the poll
+    NotRespondent(PersonId),
+    /// the person has already responded
+    AlreadyResponded(PersonId)
+}
+
+impl fmt::Display for PollError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::NotRespondent(_) => "person was not sampled for the poll",
+            Self::AlreadyResponded(_) => "person has already responded"
+        })
+    }
+}
+
+impl Error for PollError {}
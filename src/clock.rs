@@ -0,0 +1,50 @@
+//! sources of the current time for electoral procedures
+
+use chrono::{Duration, Utc};
+
+use std::sync::{Arc, Mutex};
+
+type DateTime = chrono::DateTime<chrono::Utc>;
+
+/// source of the current time, against which the dates of a procedure are
+/// checked
+pub trait Clock {
+    fn now(&self) -> DateTime;
+}
+
+/// the system time, used by default
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+/// a clock set by hand, to test date-gated transitions without waiting
+///
+/// clones share the same time, so a clone kept by the caller can advance the
+/// clock of a procedure
+#[derive(Clone, Debug)]
+pub struct MockClock(Arc<Mutex<DateTime>>);
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime {
+        Utc::now()
+    }
+}
+
+impl MockClock {
+    pub fn new(now: DateTime) -> Self {
+        Self(Arc::new(Mutex::new(now)))
+    }
+
+    pub fn set(&self, now: DateTime) {
+        *self.0.lock().unwrap() = now;
+    }
+
+    pub fn advance(&self, by: Duration) {
+        *self.0.lock().unwrap() += by;
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> DateTime {
+        *self.0.lock().unwrap()
+    }
+}
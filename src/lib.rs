@@ -1,10 +1,29 @@
 //! naive implementation of a democratic decision-making system based on
 //! majority rule
 
+// typestate transitions hand the procedure back unchanged on failure
+#![allow(clippy::result_large_err)]
+
 pub mod procedure;
 pub mod motion;
 pub mod person;
+pub mod clock;
+pub mod manager;
+pub mod census;
+pub mod store;
+pub mod sync;
+pub mod slate;
 
-pub use person::{Person, PersonList, PersonId};
-pub use motion::Motion;
-pub use procedure::Procedure;
+pub use person::{Person, PersonList, PersonId, SampleError};
+pub use motion::{Motion, MotionError, MotionCategory, Side};
+pub use procedure::{Procedure, VoteError};
+pub use clock::{Clock, SystemClock, MockClock};
+pub use manager::{ProcedureManager, ProcedureId, DependencyError};
+pub use census::{Poll, PollSummary, PollError};
+pub use store::{Store, MemoryStore};
+pub use sync::SyncProcedure;
+pub use slate::Slate;
+#[cfg(feature = "async")]
+pub use store::AsyncStore;
+#[cfg(feature = "import")]
+pub use person::ImportError;
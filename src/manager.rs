@@ -0,0 +1,254 @@
+//! tracking of many concurrent electoral procedures
+
+pub mod error;
+
+pub use error::DependencyError;
+
+use crate::Motion;
+use crate::procedure::{Procedure, ProcedureAny, StageName, Outcome};
+use crate::store::{Store, MemoryStore};
+
+use std::collections::HashMap;
+
+/// identifies a procedure within a [`ProcedureManager`]
+///
+/// IDs are never reused by the manager that issued them, even once the
+/// procedure is removed. the raw value is public for use as a storage key
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ProcedureId(pub u64);
+
+/// a collection of procedures in flight, each at its own stage, kept in a
+/// [`Store`]
+///
+/// a procedure may depend on others, in which case its motion only takes
+/// effect if theirs pass, see [`add_dependency`](Self::add_dependency).
+/// dependencies and outcomes are kept in memory, not in the store
+#[derive(Debug, Default)]
+pub struct ProcedureManager<S: Store = MemoryStore> {
+    store: S,
+    next_id: u64,
+    /// the prerequisites of each dependent procedure
+    dependencies: HashMap<ProcedureId, Vec<ProcedureId>>,
+    /// the outcome of each procedure resolved by the manager
+    outcomes: HashMap<ProcedureId, Outcome>
+}
+
+impl ProcedureManager {
+    /// a manager keeping procedures in memory
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<S: Store> ProcedureManager<S> {
+    /// a manager of the procedures already in `store`, if any
+    pub fn with_store(store: S) -> Result<Self, S::Error> {
+        let next_id = store.ids()?.last().map_or(0, |id| id.0 + 1);
+
+        Ok(Self {
+            store,
+            next_id,
+            dependencies: HashMap::new(),
+            outcomes: HashMap::new()
+        })
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    pub fn store_mut(&mut self) -> &mut S {
+        &mut self.store
+    }
+
+    pub fn len(&self) -> Result<usize, S::Error> {
+        Ok(self.store.ids()?.len())
+    }
+
+    pub fn is_empty(&self) -> Result<bool, S::Error> {
+        Ok(self.len()? == 0)
+    }
+
+    /// begins a procedure for `motion`, returning its ID
+    pub fn begin(&mut self, motion: Motion) -> Result<ProcedureId, S::Error> {
+        self.insert(Procedure::begin(motion))
+    }
+
+    /// adds a procedure at any stage, returning its ID
+    ///
+    /// the ID is used up even if the procedure could not be saved
+    pub fn insert(&mut self, procedure: impl Into<ProcedureAny>) -> Result<ProcedureId, S::Error> {
+        let id = ProcedureId(self.next_id);
+
+        self.next_id += 1;
+        self.store.save(id, procedure.into())?;
+
+        Ok(id)
+    }
+
+    pub fn get(&self, id: ProcedureId) -> Result<Option<S::ProcedureRef<'_>>, S::Error> {
+        self.store.load(id)
+    }
+
+    /// applies `change` to the procedure designated by `id`, such as a vote,
+    /// and saves it back, returning the result of `change`, or `None` if there
+    /// is no such procedure
+    ///
+    /// the procedure is taken out of the store while it is changed, so it is
+    /// lost if it cannot be saved back
+    pub fn update<R>(
+        &mut self,
+        id: ProcedureId,
+        change: impl FnOnce(&mut ProcedureAny) -> R
+    ) -> Result<Option<R>, S::Error> {
+        let Some(mut procedure) = self.store.remove(id)? else {
+            return Ok(None);
+        };
+
+        let result = change(&mut procedure);
+
+        self.store.save(id, procedure)?;
+
+        Ok(Some(result))
+    }
+
+    pub fn remove(&mut self, id: ProcedureId) -> Result<Option<ProcedureAny>, S::Error> {
+        self.store.remove(id)
+    }
+
+    /// applies `transition` to the procedure designated by `id`, such as
+    /// [`ProcedureAny::into_proposal`], returning whether it succeeded
+    ///
+    /// the procedure is kept whether the transition succeeded or not, unless
+    /// it cannot be saved back, as with [`update`](Self::update). false if
+    /// there is no such procedure
+    pub fn advance(
+        &mut self,
+        id: ProcedureId,
+        transition: impl FnOnce(ProcedureAny) -> Result<ProcedureAny, ProcedureAny>
+    ) -> Result<bool, S::Error> {
+        let Some(procedure) = self.store.remove(id)? else {
+            return Ok(false);
+        };
+
+        let (procedure, is_advanced) = match transition(procedure) {
+            Ok(p) => (p, true),
+            Err(p) => (p, false)
+        };
+
+        self.store.save(id, procedure)?;
+
+        Ok(is_advanced)
+    }
+
+    /// all procedures, in order of ID, each loaded as it is reached
+    ///
+    /// a failure to list the procedures is yielded alone
+    pub fn iter(
+        &self
+    ) -> impl Iterator<Item = Result<(ProcedureId, S::ProcedureRef<'_>), S::Error>> {
+        let (ids, error) = match self.store.ids() {
+            Ok(ids) => (ids, None),
+            Err(error) => (Vec::new(), Some(Err(error)))
+        };
+
+        let procedures = ids.into_iter().filter_map(|id| {
+            self.store.load(id).transpose().map(|procedure| procedure.map(|p| (id, p)))
+        });
+
+        error.into_iter().chain(procedures)
+    }
+
+    /// makes the motion of `dependent` conditional on that of `prerequisite`
+    /// passing, see [`resolve`](Self::resolve)
+    ///
+    /// error and does nothing if either procedure is unknown, or if
+    /// `prerequisite` already depends on `dependent`, directly or not
+    pub fn add_dependency(
+        &mut self,
+        dependent: ProcedureId,
+        prerequisite: ProcedureId
+    ) -> Result<(), DependencyError<S::Error>> {
+        for id in [dependent, prerequisite] {
+            let is_stored = self.store.load(id).map_err(DependencyError::Store)?.is_some();
+
+            if !is_stored && !self.outcomes.contains_key(&id) {
+                return Err(DependencyError::Unknown(id));
+            }
+        }
+
+        if self.depends_on(prerequisite, dependent) {
+            return Err(DependencyError::Cycle(prerequisite));
+        }
+
+        self.dependencies.entry(dependent).or_default().push(prerequisite);
+
+        Ok(())
+    }
+
+    /// the procedures on whose motions that of `id` is conditional
+    pub fn prerequisites(&self, id: ProcedureId) -> &[ProcedureId] {
+        self.dependencies.get(&id).map_or(&[], Vec::as_slice)
+    }
+
+    /// whether `id` is or depends on `other`, directly or not
+    fn depends_on(&self, id: ProcedureId, other: ProcedureId) -> bool {
+        let mut stack = vec![id];
+
+        while let Some(id) = stack.pop() {
+            if id == other {
+                return true;
+            }
+
+            stack.extend(self.prerequisites(id));
+        }
+
+        false
+    }
+
+    /// concludes the referendum of `id`, see [`Procedure::finalize`], keeping
+    /// its outcome in place of the procedure
+    ///
+    /// the motion is rejected regardless of its own tally if the motion of a
+    /// prerequisite was rejected, see [`Outcome::conditional_on`]. error and
+    /// does nothing if the procedure is unknown or not at the referendum
+    /// stage, or if a prerequisite has not been resolved
+    pub fn resolve(&mut self, id: ProcedureId) -> Result<Outcome, DependencyError<S::Error>> {
+        let prerequisites = self.prerequisites(id)
+            .iter()
+            .map(|&prereq| self.outcomes.get(&prereq).ok_or(DependencyError::Pending(prereq)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let referendum = match self.store.remove(id).map_err(DependencyError::Store)? {
+            Some(ProcedureAny::Referendum(p)) => p,
+            Some(other) => {
+                self.store.save(id, other).map_err(DependencyError::Store)?;
+
+                return Err(DependencyError::NotReferendum(id));
+            }
+            None => return Err(DependencyError::Unknown(id))
+        };
+
+        let outcome = prerequisites.into_iter()
+            .fold(referendum.finalize(), |outcome, prereq| outcome.conditional_on(prereq));
+
+        self.outcomes.insert(id, outcome);
+
+        Ok(outcome)
+    }
+
+    /// the outcome of `id`, if it was resolved by the manager
+    pub fn outcome(&self, id: ProcedureId) -> Option<Outcome> {
+        self.outcomes.get(&id).copied()
+    }
+
+    /// procedures currently at `stage`, in order of ID, see [`iter`](Self::iter)
+    pub fn in_stage(
+        &self,
+        stage: StageName
+    ) -> impl Iterator<Item = Result<(ProcedureId, S::ProcedureRef<'_>), S::Error>> {
+        self.iter().filter(move |procedure| {
+            procedure.as_ref().map_or(true, |(_, p)| p.stage() == stage)
+        })
+    }
+}
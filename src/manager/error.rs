@@ -0,0 +1,43 @@
+//! errors arising from the management of procedures
+
+use super::ProcedureId;
+
+use std::{convert::Infallible, error::Error, fmt};
+
+/// reason for which a dependency between procedures could not be set up or
+/// resolved, `E` being the error of the [`Store`](crate::Store)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DependencyError<E = Infallible> {
+    /// the manager holds no such procedure
+    Unknown(ProcedureId),
+    /// the dependency would form a cycle, so that neither procedure could be
+    /// resolved
+    Cycle(ProcedureId),
+    /// the procedure is not at the referendum stage
+    NotReferendum(ProcedureId),
+    /// the outcome of the prerequisite is not yet known
+    Pending(ProcedureId),
+    /// the store failed, see [`Error::source`]
+    Store(E)
+}
+
+impl<E> fmt::Display for DependencyError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Unknown(_) => "no such procedure",
+            Self::Cycle(_) => "dependency would form a cycle",
+            Self::NotReferendum(_) => "procedure is not at the referendum stage",
+            Self::Pending(_) => "prerequisite has not been resolved",
+            Self::Store(_) => "store failed"
+        })
+    }
+}
+
+impl<E: Error + 'static> Error for DependencyError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Store(error) => Some(error),
+            _ => None
+        }
+    }
+}
@@ -1,18 +1,134 @@
+pub mod amendment;
+pub mod category;
+pub mod argument;
+pub mod error;
+
+pub use amendment::{Amendment, Revision};
+pub use category::MotionCategory;
+pub use argument::{Argument, Side};
+pub use error::MotionError;
+
 use crate::PersonId;
 
-use std::fmt;
+use std::{collections::HashSet, fmt, sync::Arc};
 
+/// a motion subject to an electoral procedure
+///
+/// [`Motion::new`] is the validated way to construct a motion. the fields
+/// remain public, but a motion built from them directly is not checked
+#[derive(Clone, Debug)]
 pub struct Motion {
-    pub title: &'static str,
-    pub description: &'static str,
-    /// 0 contributors - anonymous motions are possible
+    pub title: String,
+    pub description: String,
+    /// none for an anonymous motion, see [`Motion::is_anonymous`]
     pub developers: Vec<PersonId>,
     /// the group of people who may be affected by the motion, and who can
     /// therefore vote on it
-    pub electors: Vec<PersonId>
+    ///
+    /// fixed for the life of the motion, so it may be shared between motions
+    /// over the same electorate, see [`Motion::new_shared`]
+    ///
+    /// kept sorted by [`Motion::new`], so that electors can be looked up by
+    /// binary search. an unsorted electorate is sorted when a procedure begins
+    pub electors: Arc<[PersonId]>,
+    /// amendments adopted during public debate, oldest first
+    pub revisions: Vec<Revision>,
+    pub category: MotionCategory,
+    /// arguments made during public debate, in order of submission
+    pub arguments: Vec<Argument>,
+    /// people who may veto the motion in the referendum, none by default, see
+    /// [`Procedure::register_veto`](crate::Procedure::register_veto)
+    pub vetoers: Vec<PersonId>
 }
 
 impl Motion {
+    /// error if the electorate is empty, if a developer is not an elector, or
+    /// if a person is listed twice as developer or as elector
+    pub fn new(
+        title: impl Into<String>,
+        description: impl Into<String>,
+        developers: Vec<PersonId>,
+        electors: Vec<PersonId>
+    ) -> Result<Self, MotionError> {
+        Self::new_shared(title, description, developers, electors.into())
+    }
+
+    /// like [`new`](Self::new), with an electorate which may be shared with
+    /// other motions rather than copied into each
+    ///
+    /// the electorate is still checked for each motion. it is only copied if
+    /// it is not already sorted
+    pub fn new_shared(
+        title: impl Into<String>,
+        description: impl Into<String>,
+        developers: Vec<PersonId>,
+        electors: Arc<[PersonId]>
+    ) -> Result<Self, MotionError> {
+        if electors.is_empty() {
+            return Err(MotionError::NoElectors);
+        }
+
+        let electors = sorted(electors);
+
+        if let Some(pair) = electors.windows(2).find(|pair| pair[0] == pair[1]) {
+            return Err(MotionError::DuplicateElector(pair[0]));
+        }
+
+        let mut developer_set = HashSet::with_capacity(developers.len());
+
+        for &id in &developers {
+            if electors.binary_search(&id).is_err() {
+                return Err(MotionError::DeveloperNotElector(id));
+            }
+
+            if !developer_set.insert(id) {
+                return Err(MotionError::DuplicateDeveloper(id));
+            }
+        }
+
+        Ok(Self {
+            title: title.into(),
+            description: description.into(),
+            developers,
+            electors,
+            revisions: Vec::new(),
+            category: MotionCategory::default(),
+            arguments: Vec::new(),
+            vetoers: Vec::new()
+        })
+    }
+
+    /// the motion, of `category` instead of [`MotionCategory::Ordinary`]
+    pub fn with_category(self, category: MotionCategory) -> Self {
+        Self { category, ..self }
+    }
+
+    /// the motion, which `vetoers` may veto
+    pub fn with_vetoers(self, vetoers: Vec<PersonId>) -> Self {
+        Self { vetoers, ..self }
+    }
+
+    /// whether the motion has no developers
+    ///
+    /// as nobody can vote in the prototype stage, an anonymous motion needs no
+    /// proposal vote and may be proposed immediately, see
+    /// [`Procedure::votes_needed`](crate::Procedure::votes_needed). it cannot be
+    /// fast-tracked
+    pub fn is_anonymous(&self) -> bool {
+        self.developers.is_empty()
+    }
+
+    /// whether `person_id` is an elector, in logarithmic time as long as the
+    /// electorate is sorted
+    pub(crate) fn is_elector(&self, person_id: PersonId) -> bool {
+        self.electors.binary_search(&person_id).is_ok()
+    }
+
+    /// sorts the electorate, if a motion was built without [`Motion::new`]
+    pub(crate) fn sort_electors(&mut self) {
+        self.electors = sorted(Arc::clone(&self.electors));
+    }
+
     pub fn dev_count(&self) -> usize {
         self.developers.len()
 
@@ -20,13 +136,60 @@ impl Motion {
     pub fn elector_count(&self) -> usize {
         self.electors.len()
     }
+
+    /// arguments for `side`, in order of submission
+    pub fn arguments_for(&self, side: Side) -> impl Iterator<Item = &Argument> {
+        self.arguments.iter().filter(move |argument| argument.side == side)
+    }
+
+    /// the motion as displayed, followed by its developer and elector counts
+    pub fn summary(&self) -> String {
+        format!(
+            "{self}\n\n{} developers, {} electors",
+            self.dev_count(),
+            self.elector_count()
+        )
+    }
+}
+
+fn sorted(electors: Arc<[PersonId]>) -> Arc<[PersonId]> {
+    if electors.windows(2).all(|pair| pair[0] <= pair[1]) {
+        return electors;
+    }
+
+    let mut electors = electors.to_vec();
+    electors.sort_unstable();
+    electors.into()
 }
 
 impl fmt::Display for Motion {
     // doesn't display developers or electorate
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        f.write_str(self.title)?;
+        f.write_str(&self.title)?;
         f.write_str("\n\n")?;
-        f.write_str(self.description)
+        f.write_str(&self.description)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Person, PersonList};
+
+    #[test]
+    fn error_names_person() {
+        let people: PersonList = ["a", "b"].into_iter()
+            .map(|name| Person { name: name.to_owned(), external_id: None })
+            .collect();
+        let ids: Vec<_> = people.ids().collect();
+
+        let error = Motion::new("motion", "", vec![ids[1]], vec![ids[0]]).unwrap_err();
+
+        assert_eq!(error, MotionError::DeveloperNotElector(ids[1]));
+        assert_eq!(error.to_string(), "developer 1 is not part of the electorate");
+
+        let error = Motion::new("motion", "", Vec::new(), vec![ids[0], ids[0]]).unwrap_err();
+
+        assert_eq!(error.to_string(), "elector 0 is listed more than once");
     }
 }
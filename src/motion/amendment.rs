@@ -0,0 +1,19 @@
+//! changes to the text of a motion during public debate
+
+/// a proposed replacement for the text of a motion
+///
+/// amendments are proposed and adopted by developers during the proposal
+/// stage, see [`Procedure::propose_amendment`](crate::Procedure::propose_amendment)
+#[derive(Clone, Debug)]
+pub struct Amendment {
+    pub title: String,
+    pub description: String
+}
+
+/// an adopted amendment, along with the text it replaced
+#[derive(Clone, Debug)]
+pub struct Revision {
+    pub previous_title: String,
+    pub previous_description: String,
+    pub amendment: Amendment
+}
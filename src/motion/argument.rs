@@ -0,0 +1,19 @@
+//! arguments made during the public debate of a motion
+
+use crate::PersonId;
+
+/// the side of the debate an argument supports
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Side {
+    For,
+    Against
+}
+
+/// an argument for or against a motion, submitted during the proposal stage,
+/// see [`Procedure::submit_argument`](crate::Procedure::submit_argument)
+#[derive(Clone, Debug)]
+pub struct Argument {
+    pub author: PersonId,
+    pub side: Side,
+    pub text: String
+}
@@ -0,0 +1,37 @@
+//! kinds of motions, subject to different rules
+
+use std::fmt;
+
+/// the kind of a motion, which determines the majority it requires, see
+/// [`Procedure::pass_by_category`](crate::Procedure::pass_by_category)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum MotionCategory {
+    #[default]
+    Ordinary,
+    /// changes to the fundamental rules of the body, requiring a
+    /// supermajority
+    Constitutional,
+    /// urgent matters
+    Emergency
+}
+
+impl MotionCategory {
+    /// the fraction of votes for among votes for and against required to pass
+    /// a motion of this category, or `None` for a simple majority
+    pub fn threshold(self) -> Option<f32> {
+        match self {
+            Self::Constitutional => Some(2.0 / 3.0),
+            Self::Ordinary | Self::Emergency => None
+        }
+    }
+}
+
+impl fmt::Display for MotionCategory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Ordinary => "ordinary",
+            Self::Constitutional => "constitutional",
+            Self::Emergency => "emergency"
+        })
+    }
+}
@@ -0,0 +1,33 @@
+//! errors arising from the construction of motions
+
+use crate::PersonId;
+
+use std::{error::Error, fmt};
+
+/// reason for which a motion is invalid, naming the offending person if any
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MotionError {
+    /// the electorate is empty, so nobody could vote on the motion
+    NoElectors,
+    /// a developer is not part of the electorate
+    DeveloperNotElector(PersonId),
+    /// a developer is listed more than once
+    DuplicateDeveloper(PersonId),
+    /// an elector is listed more than once
+    DuplicateElector(PersonId)
+}
+
+impl fmt::Display for MotionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NoElectors => f.write_str("electorate is empty"),
+            Self::DeveloperNotElector(id) => {
+                write!(f, "developer {id} is not part of the electorate")
+            }
+            Self::DuplicateDeveloper(id) => write!(f, "developer {id} is listed more than once"),
+            Self::DuplicateElector(id) => write!(f, "elector {id} is listed more than once")
+        }
+    }
+}
+
+impl Error for MotionError {}
@@ -1,9 +1,20 @@
+pub mod error;
+#[cfg(feature = "import")]
+mod import;
+
+pub use error::SampleError;
+#[cfg(feature = "import")]
+pub use error::ImportError;
+
+use rand::Rng;
+
 use std::fmt;
 
 use std::{
     ops::Index,
     fmt::Display,
-    iter::FromIterator
+    iter::FromIterator,
+    collections::HashSet
 };
 
 /// test to make sure that we can fit and index the entire population
@@ -13,95 +24,259 @@ use std::{
 const _POPULATION_FITS_USIZE: () = assert!(usize::BITS >= u64::BITS);
 
 /// data pertaining to a single individual, not necessarily unique
+#[derive(Clone, Debug)]
 pub struct Person {
-    pub name: String
+    pub name: String,
+    /// stable key from outside the system (national ID, email hash...),
+    /// independent of the person's position in a list
+    pub external_id: Option<String>
 }
 
 /// a population, with unique individuals discriminated by an ID
-/// (equivalent to the index of the person in the list)
+/// (equivalent to the index at which the person was added to the list)
+///
+/// IDs are stable: removing a person leaves an empty slot, so the IDs of the
+/// others remain valid, and an ID is never reused
 ///
 /// PersonList and PersonId are opaque to ensure validity
 // realistically this info would be stored in a DB
-pub struct PersonList(Vec<Person>);
+#[derive(Debug)]
+pub struct PersonList {
+    people: Vec<Option<Person>>,
+    /// number of empty slots left by removals
+    removed: usize
+}
 
 // u64 instead of usize because a person's ID shouldn't depend on computer
 // architecture. same with population size
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct PersonId(u64);
 
 impl PersonList {
+    /// an empty list with room for at least `capacity` people
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self { people: Vec::with_capacity(capacity), removed: 0 }
+    }
+
+    /// one person per distinct name, in the order each name is first seen
+    ///
+    /// names are compared by exact string match only: names differing in
+    /// case or whitespace are considered distinct
+    pub fn from_names_deduped(names: impl IntoIterator<Item = String>) -> Self {
+        let mut seen = HashSet::new();
+
+        names.into_iter()
+            .filter(|name| seen.insert(name.clone()))
+            .map(|name| Person { name, external_id: None })
+            .collect()
+    }
+
+    /// the number of people in the list, not counting those removed
     pub fn len(&self) -> u64 {
-        self.0.len() as _
+        (self.people.len() - self.removed) as _
     }
 
-    /// ID of random person in list
-    pub fn rand_choice(&self) -> PersonId {
-        use rand::Rng;
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
 
-        let idx = rand::thread_rng().gen_range(0..self.0.len());
+    /// ID of random person in list, or `None` if the list is empty
+    pub fn rand_choice(&self) -> Option<PersonId> {
+        self.rand_choice_with(&mut rand::thread_rng())
+    }
 
-        PersonId::from_usize(idx)
+    /// like [`rand_choice`](Self::rand_choice), drawing from `rng`, which may
+    /// be seeded for reproducibility
+    pub fn rand_choice_with(&self, rng: &mut impl Rng) -> Option<PersonId> {
+        self.sample(rng, 1).pop()
     }
 
     /// `n` unique IDs of people in list
     ///
-    /// panics if n > the number of people in the list
-    pub fn rand_choices(&self, n: u64) -> Vec<PersonId> {
+    /// error if n > the number of people in the list
+    pub fn rand_choices(&self, n: u64) -> Result<Vec<PersonId>, SampleError> {
+        self.rand_choices_with(&mut rand::thread_rng(), n)
+    }
+
+    /// like [`rand_choices`](Self::rand_choices), drawing from `rng`
+    pub fn rand_choices_with(
+        &self,
+        rng: &mut impl Rng,
+        n: u64
+    ) -> Result<Vec<PersonId>, SampleError> {
+        if n > self.len() {
+            return Err(SampleError::TooMany { requested: n, available: self.len() });
+        }
+
+        Ok(self.sample(rng, n as usize))
+    }
+
+    /// `n` unique IDs of people in list, at most as many as there are people
+    fn sample(&self, rng: &mut impl Rng, n: usize) -> Vec<PersonId> {
         use rand::seq::index;
 
-        index::sample(
-            &mut rand::thread_rng(),
-            self.0.len(),
-            n as usize
-        ).iter().map(PersonId::from_usize).collect()
+        let n = n.min(self.len() as usize);
+
+        // without removals, every slot holds a person.
+        if self.removed == 0 {
+            return index::sample(rng, self.people.len(), n)
+                .iter()
+                .map(PersonId::from_usize)
+                .collect();
+        }
+
+        let ids: Vec<_> = self.ids().collect();
+
+        index::sample(rng, ids.len(), n)
+            .iter()
+            .map(|idx| ids[idx])
+            .collect()
+    }
+
+    /// adds `person` to the end of the list, returning their ID
+    pub fn push(&mut self, person: Person) -> PersonId {
+        self.people.push(Some(person));
+
+        PersonId::from_usize(self.people.len() - 1)
+    }
+
+    /// makes room for at least `additional` more people
+    pub fn reserve(&mut self, additional: usize) {
+        self.people.reserve(additional);
+    }
+
+    /// removes the person designated by `id`, if any
+    ///
+    /// the IDs of other people are unaffected, and `id` will not designate
+    /// anyone added later, so IDs held by motions remain meaningful. the list
+    /// keeps the empty slot, which is skipped by lookups, samples and iteration
+    pub fn remove(&mut self, id: PersonId) -> Option<Person> {
+        let person = self.people.get_mut(id.0 as usize)?.take()?;
+        self.removed += 1;
+
+        Some(person)
     }
 
-    pub fn ids(&self) -> impl Iterator<Item = PersonId> {
-        (0..self.0.len())
+    /// the person designated by `id`, or `None` if there is none, unlike
+    /// indexing which panics
+    pub fn get(&self, id: PersonId) -> Option<&Person> {
+        self.people.get(id.0 as usize)?.as_ref()
+    }
+
+    /// whether `id` designates a person in this list, who was not removed
+    pub fn contains(&self, id: PersonId) -> bool {
+        self.get(id).is_some()
+    }
+
+    /// the ID of the person added at index `n` of the list, or `None` if there
+    /// is none, for instance to restore an ID received from outside
+    pub fn id_from_u64(&self, n: u64) -> Option<PersonId> {
+        Some(PersonId(n)).filter(|&id| self.contains(id))
+    }
+
+    /// the ID of the first person whose external ID is `key`, if any
+    ///
+    /// runs in linear time
+    pub fn find_by_external_id(&self, key: &str) -> Option<PersonId> {
+        self.people.iter()
+            .position(|person| {
+                person.as_ref().is_some_and(|p| p.external_id.as_deref() == Some(key))
+            })
             .map(PersonId::from_usize)
     }
+
+    /// equivalent to [`contains`](Self::contains)
+    ///
+    /// IDs are only meaningful relative to the list they were obtained from, so
+    /// any ID originating from outside, such as from persisted data, should be
+    /// validated before use
+    pub fn validate_id(&self, id: PersonId) -> bool {
+        self.contains(id)
+    }
+
+    /// people in list, in order of ID
+    pub fn iter(&self) -> Iter<'_> {
+        self.people.iter().flatten()
+    }
+
+    /// IDs of people in list, in increasing order
+    pub fn ids(&self) -> impl Iterator<Item = PersonId> + '_ {
+        self.people.iter()
+            .enumerate()
+            .filter(|(_, person)| person.is_some())
+            .map(|(idx, _)| PersonId::from_usize(idx))
+    }
 }
 
+/// iterator over the people in a [`PersonList`], see [`PersonList::iter`]
+pub type Iter<'a> = std::iter::Flatten<std::slice::Iter<'a, Option<Person>>>;
+
 impl Index<PersonId> for PersonList {
     type Output = Person;
 
+    /// panics if `idx` designates nobody, as after a removal
     fn index(&self, idx: PersonId) -> &Person {
-        // `PersonId` is a valid `usize` index into `PersonList`.
-        &self.0[idx.0 as usize]
+        self.get(idx).expect("no person with this ID")
+    }
+}
+
+impl Display for Person {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.name)
+    }
+}
+
+/// the raw ID, as accepted by [`PersonList::id_from_u64`]
+impl Display for PersonId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
     }
 }
 
 impl Display for PersonList {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let mut person_iter = self.0.iter();
+        let mut person_iter = self.iter();
 
         if let Some(p) = person_iter.next() {
-            write!(f, "{}", p.name)?;
+            write!(f, "{p}")?;
         }
 
         for p in person_iter {
-            write!(f, "\n{}", p.name)?;
+            write!(f, "\n{p}")?;
         }
 
         Ok(())
     }
 }
 
-//impl<'a> IntoIterator for &'a PersonList {
-//    type Item = &'a Person;
-//    type IntoIter = std::slice::Iter<'a, Person>;
-//
-//    fn into_iter(self) -> Self::IntoIter {
-//        self.0.iter()
-//    }
-//}
+impl<'a> IntoIterator for &'a PersonList {
+    type Item = &'a Person;
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
 
 impl FromIterator<Person> for PersonList {
     fn from_iter<I>(iter: I) -> Self
         where
             I: IntoIterator<Item = Person>
     {
-        Self(iter.into_iter().collect())
+        Self {
+            people: iter.into_iter().map(Some).collect(),
+            removed: 0
+        }
+    }
+}
+
+impl Extend<Person> for PersonList {
+    /// people are appended in order, so existing IDs remain valid
+    fn extend<I>(&mut self, iter: I)
+        where
+            I: IntoIterator<Item = Person>
+    {
+        self.people.extend(iter.into_iter().map(Some));
     }
 }
 
@@ -0,0 +1,73 @@
+//! errors arising from operations on populations
+
+use std::{error::Error, fmt};
+#[cfg(feature = "import")]
+use std::io;
+
+/// reason for which people could not be sampled from a population
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SampleError {
+    /// more people were requested than the population contains
+    TooMany {
+        requested: u64,
+        available: u64
+    }
+}
+
+impl fmt::Display for SampleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::TooMany { requested, available } => write!(
+                f,
+                "cannot sample {requested} people from a population of {available}"
+            )
+        }
+    }
+}
+
+impl Error for SampleError {}
+
+/// reason for which a population could not be imported
+#[cfg(feature = "import")]
+#[derive(Debug)]
+pub enum ImportError {
+    /// the input could not be read
+    Io(io::Error),
+    /// the input is empty
+    MissingHeader,
+    /// the header has no `name` column
+    MissingNameColumn,
+    /// the row on `line` (starting from 1) has an empty or absent name
+    MissingName {
+        line: usize
+    }
+}
+
+#[cfg(feature = "import")]
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "could not read population: {e}"),
+            Self::MissingHeader => f.write_str("population has no header"),
+            Self::MissingNameColumn => f.write_str("header has no name column"),
+            Self::MissingName { line } => write!(f, "row on line {line} has no name")
+        }
+    }
+}
+
+#[cfg(feature = "import")]
+impl Error for ImportError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None
+        }
+    }
+}
+
+#[cfg(feature = "import")]
+impl From<io::Error> for ImportError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
@@ -0,0 +1,50 @@
+//! populations read from simple comma-separated files
+
+use super::{Person, PersonList, ImportError};
+
+use std::io::{BufRead, BufReader, Read};
+
+impl PersonList {
+    /// reads one person per row of a CSV whose header contains a `name`
+    /// column, and optionally an `external_id` column whose empty fields are
+    /// read as `None`. blank lines are skipped
+    ///
+    /// only unquoted fields are supported: commas cannot be escaped, and
+    /// quotes are kept as part of the field. surrounding whitespace is
+    /// trimmed from each field
+    pub fn from_csv(reader: impl Read) -> Result<Self, ImportError> {
+        let mut lines = BufReader::new(reader).lines();
+
+        let header = lines.next().ok_or(ImportError::MissingHeader)??;
+        let column = |name| header.split(',').position(|field| field.trim() == name);
+        let name_column = column("name").ok_or(ImportError::MissingNameColumn)?;
+        let id_column = column("external_id");
+
+        let mut persons = Vec::new();
+
+        // the header is line 1
+        for (line, row) in (2..).zip(lines) {
+            let row = row?;
+
+            if row.trim().is_empty() {
+                continue;
+            }
+
+            let fields: Vec<_> = row.split(',').map(str::trim).collect();
+
+            let name = fields.get(name_column)
+                .filter(|name| !name.is_empty())
+                .ok_or(ImportError::MissingName { line })?;
+            let external_id = id_column
+                .and_then(|idx| fields.get(idx))
+                .filter(|id| !id.is_empty());
+
+            persons.push(Person {
+                name: (*name).to_owned(),
+                external_id: external_id.map(|id| (*id).to_owned())
+            });
+        }
+
+        Ok(persons.into_iter().collect())
+    }
+}
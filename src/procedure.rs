@@ -1,9 +1,58 @@
-use crate::{Motion, PersonId};
+pub mod error;
+pub mod any;
+pub mod ranked;
+pub mod commit_reveal;
+pub mod secret;
+pub mod quadratic;
+pub mod districted;
+pub mod history;
+pub mod outcome;
+pub mod token;
+pub mod stats;
+pub mod retired;
+pub mod observer;
+pub mod pipeline;
+#[cfg(feature = "async")]
+mod nonblocking;
+#[cfg(test)]
+mod tests;
 
-use chrono::{Duration, Utc};
+pub use error::{VoteError, PassError, PipelineError};
+pub use any::ProcedureAny;
+pub use ranked::RankedReferendum;
+pub use commit_reveal::CommitRevealReferendum;
+pub use secret::{SecretReferendum, IssuedTokens};
+pub use quadratic::{QuadraticReferendum, QUADRATIC_BUDGET};
+pub use districted::{DistrictedReferendum, DistrictId, DistrictTally};
+pub use history::{StageName, TransitionRecord, Timeline};
+pub use outcome::{Outcome, ReferendumResult, TieBreak};
+pub use token::BallotToken;
+pub use stats::Stats;
+pub use retired::Retired;
+pub use observer::{VoteObserver, TransitionObserver};
+pub use pipeline::Pipeline;
+
+use crate::{Motion, MotionCategory, PersonId, Clock, SystemClock};
+use crate::motion::{Amendment, Revision, Argument, Side};
+use observer::Observers;
+
+use chrono::Duration;
+
+use rand::Rng;
+
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+    sync::Arc,
+    fmt
+};
 
 type DateTime = chrono::DateTime<chrono::Utc>;
 
+/// additional condition for electors to be allowed to vote, see
+/// [`Procedure::set_eligibility`]
+pub type Eligibility = Arc<dyn Fn(PersonId) -> bool + Send + Sync>;
+
 /// an electoral procedure for passing motions
 ///
 /// ## development
@@ -51,10 +100,17 @@ type DateTime = chrono::DateTime<chrono::Utc>;
 /// every vote, approval, signature... can be done in total secrecy, without the
 /// identity of the voter being known during or after the fact. this is to
 /// ensure that all voters can express their will independently and without
-/// external influence, interference, or intimidation.
+/// external influence, interference, or intimidation. see [`SecretReferendum`]
+/// for a referendum holding no record of who has voted.
 pub struct Procedure<St: ProcedureStage> {
     motion: Motion,
-    stage: St
+    stage: St,
+    /// every transition since the beginning of the procedure, oldest first
+    history: Vec<TransitionRecord>,
+    eligibility: Option<Eligibility>,
+    /// source of the time for every date of the procedure
+    clock: Arc<dyn Clock + Send + Sync>,
+    observers: Observers
 }
 
 // realistically, voters/approvers... would be stored in DB
@@ -65,7 +121,9 @@ pub struct Procedure<St: ProcedureStage> {
 /// typestate for electoral procedure
 ///
 /// sealed trait
-pub trait ProcedureStage: sealed::Sealed {}
+pub trait ProcedureStage: sealed::Sealed + fmt::Debug {
+    const NAME: StageName;
+}
 
 /// developpment until majority of developpers vote to propose
 ///
@@ -75,10 +133,26 @@ pub trait ProcedureStage: sealed::Sealed {}
 ///
 /// minimum requiered number of votes to propose is  the number of
 /// developpers / 2 + 1
+#[derive(Clone, Debug)]
 pub struct Prototype {
+    /// the latest proposal vote, which may have expired
+    attempt: Option<ProposalAttempt>,
+    /// developers who have voted to add each candidate as developer
+    additions: HashMap<PersonId, HashSet<PersonId>>,
+    /// developers who have voted to remove each developer
+    removals: HashMap<PersonId, HashSet<PersonId>>,
+    /// developers who have voted to retire the motion
+    retire_votes: HashSet<PersonId>
+}
+
+/// a proposal vote, open until its end date
+#[derive(Clone, Debug)]
+struct ProposalAttempt {
+    start_date: DateTime,
+    end_date: DateTime,
     /// all voters are developers, listed in the motion
-    have_voted: Vec<PersonId>,
-    proposal_votes: u64
+    have_voted: HashSet<PersonId>,
+    votes: u64
 }
 
 /// development is frozen and public debate until certain date is reached, set
@@ -87,9 +161,28 @@ pub struct Prototype {
 /// developers can vote at any time to return motion to prototype state
 ///
 /// parties for and against the motion engage in fair debate, such that the
-/// electorate is educated before making a decision
+/// electorate is educated before making a decision. developers may amend the
+/// motion in light of the debate by majority vote
+#[derive(Clone, Debug)]
 pub struct Proposal {
-    end_date: DateTime
+    end_date: DateTime,
+    /// developers who have voted to return to prototype
+    have_voted: HashSet<PersonId>,
+    revert_votes: u64,
+    amendments: Vec<PendingAmendment>,
+    /// developers who have voted to extend the debate by each duration
+    extensions: HashMap<Duration, HashSet<PersonId>>,
+    extension_count: u64,
+    /// developers who have voted to skip the petition
+    fast_track_votes: HashSet<PersonId>
+}
+
+/// an amendment awaiting adoption by the developers
+#[derive(Clone, Debug)]
+struct PendingAmendment {
+    amendment: Amendment,
+    have_voted: HashSet<PersonId>,
+    votes: u64
 }
 
 /// shown to a limited set of random individuals from the electorate for
@@ -98,69 +191,502 @@ pub struct Proposal {
 /// vote sincerely in accordance with the accepted principles
 ///
 /// if absolute majority of electorate approves, motion is selected for vote
+#[derive(Clone, Debug)]
 pub struct Petition {
     voter_ids: Vec<PersonId>,
-    have_voted: Vec<PersonId>,
-    approval_votes: u64
+    have_voted: HashSet<PersonId>,
+    /// the proxy through whom each petitioner voted, if any
+    proxies: HashMap<PersonId, PersonId>,
+    approval_votes: u64,
+    /// the time of each vote, not associated with voters
+    vote_times: Vec<DateTime>,
+    /// 1 for the first petition after the debate, see
+    /// [`Procedure::into_next_petition`]
+    round: u64
 }
 
 /// motion is carried when there are more votes for than votes against
+///
+/// electors may also abstain, which counts as participation without
+/// affecting the outcome
+///
+/// an elector may delegate their vote to another, who may themselves delegate
+/// it further. an elector who does not vote is counted as having voted like the
+/// first person down their chain of delegation who did
+///
+/// electors may instead be issued a [`BallotToken`] to vote in secret, in which
+/// case only the issue of the token is recorded, not whether or how it was
+/// used
+///
+/// voting closes at a set date
+#[derive(Clone, Debug)]
 pub struct Referendum {
-    have_voted: Vec<PersonId>,
-    /// votes for adoption.
+    end_date: DateTime,
+    /// the current ballot of each voter
+    ///
+    /// kept so that voters may change their mind while voting is open
+    have_voted: HashMap<PersonId, Ballot>,
+    /// the person to whom each delegating elector has delegated their vote
+    delegations: HashMap<PersonId, PersonId>,
+    /// the proxy through whom each voter voted, if any
+    proxies: HashMap<PersonId, PersonId>,
+    /// weighted votes for adoption, excluding delegated votes.
+    votes_for: u64,
+    /// weighted votes against adoption, excluding delegated votes.
+    votes_against: u64,
+    abstentions: u64,
+    spoiled: u64,
+    /// the time of each ballot, not associated with voters
+    vote_times: Vec<DateTime>,
+    /// electors who were issued a token, and may no longer vote otherwise
+    token_holders: HashSet<PersonId>,
+    /// tokens issued but not yet spent, unrelated to their holders
+    unspent_tokens: HashSet<BallotToken>,
+    /// ballots cast by token, abstentions included
+    secret_ballots: u64,
+    /// the date until which each voter has locked their participation, see
+    /// [`Procedure::register_vote_for_locked`]
+    locks: HashMap<PersonId, DateTime>,
+    /// vetoers who have vetoed the motion, in order
+    vetoes: Vec<PersonId>
+}
+
+/// referendum results, accounting for delegation
+struct Tally {
     votes_for: u64,
-    /// votes against adoption.
     votes_against: u64,
+    /// electors whose vote was cast, directly or through delegation
+    voters: u64
+}
+
+/// a ballot cast in the referendum, and the weight of the voter
+#[derive(Clone, Copy, Debug)]
+struct Ballot {
+    choice: Choice,
+    weight: u64
 }
 
-impl ProcedureStage for Prototype {}
-impl ProcedureStage for Proposal {}
-impl ProcedureStage for Petition {}
-impl ProcedureStage for Referendum {}
+/// a ballot cast in the referendum
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Choice {
+    For,
+    Against,
+    Abstain,
+    Spoiled
+}
+
+/// motion was carried by referendum, concluding the procedure
+#[derive(Clone, Debug)]
+pub struct Passed;
+
+impl ProcedureStage for Prototype { const NAME: StageName = StageName::Prototype; }
+impl ProcedureStage for Proposal { const NAME: StageName = StageName::Proposal; }
+impl ProcedureStage for Petition { const NAME: StageName = StageName::Petition; }
+impl ProcedureStage for Referendum { const NAME: StageName = StageName::Referendum; }
+impl ProcedureStage for Passed { const NAME: StageName = StageName::Passed; }
 
 impl<St: ProcedureStage> Procedure<St> {
     pub fn motion(&self) -> &Motion {
         &self.motion
     }
+
+    pub fn stage_name(&self) -> StageName {
+        St::NAME
+    }
+
+    /// every stage transition since the beginning of the procedure, including
+    /// the beginning itself, oldest first
+    pub fn history(&self) -> &[TransitionRecord] {
+        &self.history
+    }
+
+    /// restricts the electors allowed to vote in the petition and referendum
+    /// stages to those for whom `eligibility` is true, on top of membership of
+    /// the electorate, for instance to require registration by a cutoff date.
+    /// ineligible votes are rejected with [`VoteError::Ineligible`]
+    ///
+    /// by default, every elector is eligible. the condition is kept across
+    /// transitions
+    pub fn set_eligibility(
+        &mut self,
+        eligibility: impl Fn(PersonId) -> bool + Send + Sync + 'static
+    ) {
+        self.eligibility = Some(Arc::new(eligibility));
+    }
+
+    pub fn clear_eligibility(&mut self) {
+        self.eligibility = None;
+    }
+
+    fn is_eligible(&self, person_id: PersonId) -> bool {
+        self.eligibility.as_ref().is_none_or(|is_eligible| is_eligible(person_id))
+    }
+
+    /// replaces the clock against which dates are checked, for instance with a
+    /// [`MockClock`](crate::MockClock) in tests. the clock is kept across
+    /// transitions
+    pub fn set_clock(&mut self, clock: impl Clock + Send + Sync + 'static) {
+        self.clock = Arc::new(clock);
+    }
+
+    /// registers `observer` to be called whenever a vote of any kind is
+    /// registered, including changed votes, but not delegations
+    ///
+    /// observers are kept across transitions, but not by clones
+    pub fn on_vote(&mut self, observer: VoteObserver) {
+        self.observers.on_vote.push(observer);
+    }
+
+    /// registers `observer` to be called after each transition, see
+    /// [`on_vote`](Self::on_vote)
+    pub fn on_transition(&mut self, observer: TransitionObserver) {
+        self.observers.on_transition.push(observer);
+    }
+
+    fn notify_vote(&mut self) {
+        for observer in &mut self.observers.on_vote {
+            observer(St::NAME);
+        }
+    }
+
+    /// moves the procedure to `stage`, recording the transition
+    fn transition<To: ProcedureStage>(mut self, stage: To) -> Procedure<To> {
+        let record = TransitionRecord {
+            from: Some(St::NAME),
+            to: To::NAME,
+            at: self.clock.now()
+        };
+
+        self.history.push(record);
+
+        for observer in &mut self.observers.on_transition {
+            observer(&record);
+        }
+
+        Procedure {
+            motion: self.motion,
+            stage,
+            history: self.history,
+            eligibility: self.eligibility,
+            clock: self.clock,
+            observers: self.observers
+        }
+    }
+}
+
+// the eligibility condition and clock are opaque, so only the presence of the
+// former is shown
+impl<St: ProcedureStage> fmt::Debug for Procedure<St> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Procedure")
+            .field("motion", &self.motion)
+            .field("stage", &self.stage)
+            .field("history", &self.history)
+            .field("has_eligibility", &self.eligibility.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<St: ProcedureStage + Clone> Clone for Procedure<St> {
+    fn clone(&self) -> Self {
+        Self {
+            motion: self.motion.clone(),
+            stage: self.stage.clone(),
+            history: self.history.clone(),
+            eligibility: self.eligibility.clone(),
+            clock: self.clock.clone(),
+            observers: Observers::default()
+        }
+    }
+}
+
+impl Prototype {
+    fn new() -> Self {
+        Self {
+            attempt: None,
+            additions: HashMap::new(),
+            removals: HashMap::new(),
+            retire_votes: HashSet::new()
+        }
+    }
 }
 
 impl Procedure<Prototype> {
     pub fn begin(motion: Motion) -> Self {
-        Self { motion, stage: Prototype {
-            have_voted: Vec::new(),
-            proposal_votes: 0
-        }}
+        Self::begin_with_clock(motion, SystemClock)
+    }
+
+    /// like [`begin`](Self::begin), with dates taken from `clock`, see
+    /// [`set_clock`](Self::set_clock)
+    pub fn begin_with_clock(
+        mut motion: Motion,
+        clock: impl Clock + Send + Sync + 'static
+    ) -> Self {
+        motion.sort_electors();
+
+        let history = vec![TransitionRecord {
+            from: None,
+            to: StageName::Prototype,
+            at: clock.now()
+        }];
+
+        Self {
+            motion,
+            stage: Prototype::new(),
+            history,
+            eligibility: None,
+            clock: Arc::new(clock),
+            observers: Observers::default()
+        }
+    }
+
+    /// opens a proposal vote for `duration`, returning whether it was opened,
+    /// which is not the case while another is active, or if `duration` would
+    /// end it past the latest representable date
+    ///
+    /// the votes of an expired proposal vote are discarded
+    pub fn start_proposal_attempt(&mut self, duration: Duration) -> bool {
+        if self.active_attempt().is_some() {
+            return false;
+        }
+
+        let start_date = self.clock.now();
+
+        let Some(end_date) = start_date.checked_add_signed(duration) else {
+            return false;
+        };
+
+        self.stage.attempt = Some(ProposalAttempt {
+            start_date,
+            end_date,
+            have_voted: HashSet::new(),
+            votes: 0
+        });
+
+        true
     }
 
+    /// the start and end dates of the active proposal vote, if any
+    pub fn proposal_attempt_dates(&self) -> Option<(DateTime, DateTime)> {
+        self.active_attempt().map(|attempt| (attempt.start_date, attempt.end_date))
+    }
+
+    /// votes of the active proposal vote, or 0 if there is none
     pub fn proposal_votes(&self) -> u64 {
-        self.stage.proposal_votes
+        self.active_attempt().map_or(0, |attempt| attempt.votes)
     }
 
-    /// error and does nothing if `person_id` has already voted or is not
+    /// error and does nothing if no proposal vote is active, or if `person_id`
+    /// has already voted or is not developper, see [`VoteError`]
+    pub fn register_proposal_vote(&mut self, person_id: PersonId) -> Result<(), VoteError> {
+        if !self.motion.developers.contains(&person_id) {
+            return Err(VoteError::NotDeveloper(person_id));
+        }
+
+        let now = self.clock.now();
+
+        let Some(attempt) = self.stage.attempt.as_mut().filter(|a| now < a.end_date) else {
+            return Err(VoteError::Closed);
+        };
+
+        if attempt.have_voted.contains(&person_id) {
+            return Err(VoteError::AlreadyVoted(person_id));
+        }
+
+        attempt.votes += 1;
+        attempt.have_voted.insert(person_id);
+
+        self.notify_vote();
+
+        Ok(())
+    }
+
+    fn active_attempt(&self) -> Option<&ProposalAttempt> {
+        let now = self.clock.now();
+
+        self.stage.attempt.as_ref().filter(|attempt| now < attempt.end_date)
+    }
+
+    /// vote by `voter` to make `candidate` a developer, which happens as soon
+    /// as an absolute majority of the current developers has voted for it
+    ///
+    /// error and does nothing if `voter` has already voted for `candidate` or
+    /// is not developper, or if `candidate` is not an elector or is already
     /// developper
-    pub fn register_proposal_vote(&mut self, person_id: PersonId) -> Result<(), ()> {
-        let is_valid = self.motion.developers.contains(&person_id)
-            && !self.stage.have_voted.contains(&person_id);
+    pub fn register_add_developer_vote(
+        &mut self,
+        voter: PersonId,
+        candidate: PersonId
+    ) -> Result<(), VoteError> {
+        if !self.motion.developers.contains(&voter) {
+            return Err(VoteError::NotDeveloper(voter));
+        }
+
+        if !self.motion.is_elector(candidate) {
+            return Err(VoteError::NotElector(candidate));
+        }
+
+        if self.motion.developers.contains(&candidate) {
+            return Err(VoteError::AlreadyDeveloper(candidate));
+        }
+
+        let votes = self.stage.additions.entry(candidate).or_default();
+
+        if !votes.insert(voter) {
+            return Err(VoteError::AlreadyVoted(voter));
+        }
 
-        if is_valid {
-            self.stage.proposal_votes += 1;
-            self.stage.have_voted.push(person_id);
+        if votes.len() as u64 >= absolute_majority(self.motion.dev_count()) {
+            self.stage.additions.remove(&candidate);
+            self.motion.developers.push(candidate);
+        }
+
+        self.notify_vote();
+
+        Ok(())
+    }
+
+    /// vote by `voter` to remove `candidate` from the developers, which happens
+    /// as soon as an absolute majority of the current developers has voted for
+    /// it. any vote previously cast by `candidate` in this stage is withdrawn
+    ///
+    /// error and does nothing if `voter` has already voted against `candidate`,
+    /// or if either is not developper
+    pub fn register_remove_developer_vote(
+        &mut self,
+        voter: PersonId,
+        candidate: PersonId
+    ) -> Result<(), VoteError> {
+        for id in [voter, candidate] {
+            if !self.motion.developers.contains(&id) {
+                return Err(VoteError::NotDeveloper(id));
+            }
+        }
+
+        let votes = self.stage.removals.entry(candidate).or_default();
+
+        if !votes.insert(voter) {
+            return Err(VoteError::AlreadyVoted(voter));
+        }
+
+        if votes.len() as u64 >= absolute_majority(self.motion.dev_count()) {
+            self.remove_developer(candidate);
+        }
+
+        self.notify_vote();
+
+        Ok(())
+    }
+
+    fn remove_developer(&mut self, person_id: PersonId) {
+        self.motion.developers.retain(|&id| id != person_id);
+        self.stage.removals.remove(&person_id);
+        self.stage.retire_votes.remove(&person_id);
+
+        if let Some(attempt) = &mut self.stage.attempt {
+            if attempt.have_voted.remove(&person_id) {
+                attempt.votes -= 1;
+            }
+        }
+
+        let pending = self.stage.additions.values_mut()
+            .chain(self.stage.removals.values_mut());
+
+        for votes in pending {
+            votes.remove(&person_id);
+        }
+    }
 
-            Ok(())
+    /// the number of proposal votes required to propose the motion: an
+    /// absolute majority of developers, or none if the motion is anonymous
+    pub fn votes_needed(&self) -> u64 {
+        if self.motion.is_anonymous() {
+            0
         } else {
-            Err(())
+            absolute_majority(self.motion.dev_count())
         }
     }
 
-    /// returns Err(self) unchanged if not enough votes
+    /// fraction of the required proposal votes obtained, from 0 to 1
+    pub fn progress(&self) -> f32 {
+        progress(self.proposal_votes(), self.votes_needed())
+    }
+
+    /// the proposal votes still required to propose the motion, 0 once there
+    /// are enough
+    pub fn votes_short(&self) -> u64 {
+        self.votes_needed().saturating_sub(self.proposal_votes())
+    }
+
+    /// whether the active proposal vote has enough votes to propose the motion,
+    /// see [`votes_short`](Self::votes_short) otherwise
+    ///
+    /// [`into_proposal`](Self::into_proposal) may still fail if the debate
+    /// would be too short
+    pub fn can_propose(&self) -> bool {
+        self.votes_short() == 0
+    }
+
+    /// returns Err(self) unchanged if the active proposal vote does not have
+    /// enough votes, or if there is none (unless the motion is anonymous), or
+    /// if `prop_time` is shorter than [`MIN_PROPOSAL_DURATION`]. see
+    /// [`can_propose`](Self::can_propose) to check beforehand
     pub fn into_proposal(self, prop_time: Duration) -> Result<Procedure<Proposal>, Self> {
-        let half = self.motion.developers.len() as u64 / 2;
+        // negative durations cannot be converted.
+        let is_too_short = prop_time.to_std()
+            .map_or(true, |prop_time| prop_time < MIN_PROPOSAL_DURATION);
 
-        if self.stage.proposal_votes > half {
-            Ok(Procedure {
-                motion: self.motion,
-                stage: Proposal { end_date: Utc::now() + prop_time }
-            })
+        if is_too_short {
+            return Err(self);
+        }
+
+        self.into_proposal_unchecked(prop_time)
+    }
+
+    /// like [`into_proposal`](Self::into_proposal), with no minimum to
+    /// `prop_time`
+    pub fn into_proposal_unchecked(
+        self,
+        prop_time: Duration
+    ) -> Result<Procedure<Proposal>, Self> {
+        if self.can_propose() {
+            let end_date = self.clock.now() + prop_time;
+
+            Ok(self.transition(Proposal {
+                end_date,
+                have_voted: HashSet::new(),
+                revert_votes: 0,
+                amendments: Vec::new(),
+                extensions: HashMap::new(),
+                extension_count: 0,
+                fast_track_votes: HashSet::new()
+            }))
+        } else {
+            Err(self)
+        }
+    }
+
+    /// moves straight to the referendum, open to votes for `vote_time`,
+    /// skipping both the debate and the petition
+    ///
+    /// like [`Procedure::into_referendum_fast_track`] from the proposal, this
+    /// is reserved for [`MotionCategory::Emergency`] motions with developers.
+    /// as there is no debate in which to vote for the fast-track, the active
+    /// proposal vote must instead be backed by two thirds of developers.
+    /// returns Err(self) unchanged otherwise
+    pub fn into_referendum_fast_track(
+        self,
+        vote_time: Duration
+    ) -> Result<Procedure<Referendum>, Self> {
+        let may_skip = self.motion.category == MotionCategory::Emergency
+            && !self.motion.is_anonymous()
+            && self.proposal_votes() >= supermajority(self.motion.dev_count());
+
+        if may_skip {
+            let end_date = self.clock.now() + vote_time;
+            let referendum = Referendum::new(end_date);
+
+            Ok(self.transition(referendum))
         } else {
             Err(self)
         }
@@ -172,125 +698,1497 @@ impl Procedure<Proposal> {
         self.stage.end_date
     }
 
-    /// returns Err if proposal end date has not been reached
-    pub fn into_petition(self) -> Result<Procedure<Petition>, Self> {
-        use rand::seq::SliceRandom;
+    /// time left until the end of the debate, zero once it is over
+    pub fn time_remaining(&self) -> Duration {
+        (self.stage.end_date - self.clock.now()).max(Duration::zero())
+    }
 
-        if self.stage.end_date <= Utc::now() {
-            let petitioner_count = self.motion.electors.len() as f32 * PETITIONER_RATIO;
+    /// whether [`into_petition`](Self::into_petition) would succeed now: the
+    /// debate is over and the electorate is not empty
+    pub fn is_ready_for_petition(&self) -> bool {
+        self.stage.end_date <= self.clock.now() && !self.motion.electors.is_empty()
+    }
 
-            let voter_ids = self.motion.electors.choose_multiple(
-                &mut rand::thread_rng(),
-                petitioner_count as usize
-            ).copied().collect::<Vec<_>>();
+    pub fn revert_votes(&self) -> u64 {
+        self.stage.revert_votes
+    }
 
-            Ok(Procedure {
-                motion: self.motion,
-                stage: Petition {
-                    voter_ids,
-                    have_voted: Vec::new(),
-                    approval_votes: 0
-                }
-            })
-        } else {
-            Err(self)
+    /// vote to return the motion to prototype for further development
+    ///
+    /// error and does nothing if `person_id` has already voted or is not
+    /// developper, see [`VoteError`]
+    pub fn register_revert_vote(&mut self, person_id: PersonId) -> Result<(), VoteError> {
+        if !self.motion.developers.contains(&person_id) {
+            return Err(VoteError::NotDeveloper(person_id));
+        }
+
+        if self.stage.have_voted.contains(&person_id) {
+            return Err(VoteError::AlreadyVoted(person_id));
         }
+
+        self.stage.revert_votes += 1;
+        self.stage.have_voted.insert(person_id);
+
+        self.notify_vote();
+
+        Ok(())
     }
-}
 
-/// the size of the petitioner group relative to population
-///
-/// in reality this would be a dynamic value, inversely proportional to the size
-/// of the population
-pub const PETITIONER_RATIO: f32 = 0.25;
+    /// vote by `voter` to extend the debate by `extra`, which happens as soon
+    /// as an absolute majority of developers has voted for the same extension.
+    /// all pending extension votes are then discarded
+    ///
+    /// error and does nothing if the debate is over, if `voter` is not
+    /// developper or has already voted for this extension, or if `extra` is
+    /// not positive or would end the debate past the latest representable date
+    pub fn register_extension_vote(
+        &mut self,
+        voter: PersonId,
+        extra: Duration
+    ) -> Result<(), VoteError> {
+        if self.stage.end_date <= self.clock.now() {
+            return Err(VoteError::Closed);
+        }
 
-impl Procedure<Petition> {
-    pub fn votes_for(&self) -> u64 {
-        self.stage.approval_votes
+        if !self.motion.developers.contains(&voter) {
+            return Err(VoteError::NotDeveloper(voter));
+        }
+
+        if extra <= Duration::zero() {
+            return Err(VoteError::InvalidBallot(voter));
+        }
+
+        // pending votes are discarded whenever the end date changes, so the
+        // extended date stays valid until the extension is granted.
+        let extended_date = self.stage.end_date
+            .checked_add_signed(extra)
+            .ok_or(VoteError::InvalidBallot(voter))?;
+
+        let votes = self.stage.extensions.entry(extra).or_default();
+
+        if !votes.insert(voter) {
+            return Err(VoteError::AlreadyVoted(voter));
+        }
+
+        if votes.len() as u64 >= absolute_majority(self.motion.dev_count()) {
+            self.stage.extensions.clear();
+            self.stage.end_date = extended_date;
+            self.stage.extension_count += 1;
+        }
+
+        self.notify_vote();
+
+        Ok(())
     }
 
-    pub fn voter_ids(&self) -> &[PersonId] {
-        &self.stage.voter_ids
+    /// the number of extensions of the debate granted so far
+    pub fn extension_count(&self) -> u64 {
+        self.stage.extension_count
+    }
+
+    /// vote to skip the petition, see
+    /// [`into_referendum_fast_track`](Self::into_referendum_fast_track)
+    ///
+    /// error and does nothing if `person_id` has already voted or is not
+    /// developper
+    pub fn register_fast_track_vote(&mut self, person_id: PersonId) -> Result<(), VoteError> {
+        if !self.motion.developers.contains(&person_id) {
+            return Err(VoteError::NotDeveloper(person_id));
+        }
+
+        if !self.stage.fast_track_votes.insert(person_id) {
+            return Err(VoteError::AlreadyVoted(person_id));
+        }
+
+        self.notify_vote();
+
+        Ok(())
     }
 
-    pub fn register_approval_vote(&mut self, person_id: PersonId) -> Result<(), ()> {
-        let is_valid = self.motion.electors.contains(&person_id)
-            && !self.stage.have_voted.contains(&person_id);
+    pub fn fast_track_votes(&self) -> u64 {
+        self.stage.fast_track_votes.len() as u64
+    }
 
-        if is_valid {
-            self.stage.approval_votes += 1;
-            self.stage.have_voted.push(person_id);
+    /// the number of fast-track votes required to skip the petition: two
+    /// thirds of developers, above the absolute majority required elsewhere
+    pub fn fast_track_votes_needed(&self) -> u64 {
+        supermajority(self.motion.dev_count())
+    }
 
-            Ok(())
-        } else {
-            Err(())
+    /// records an argument by `author` for `side` of the debate in
+    /// [`Motion::arguments`], where it remains for the rest of the procedure.
+    /// returns the index of the argument
+    ///
+    /// both sides may be argued by anyone, so that the electorate hears both.
+    /// error and does nothing if `author` is neither developer nor elector
+    pub fn submit_argument(
+        &mut self,
+        author: PersonId,
+        side: Side,
+        text: impl Into<String>
+    ) -> Result<usize, VoteError> {
+        let may_argue = self.motion.developers.contains(&author)
+            || self.motion.is_elector(author);
+
+        if !may_argue {
+            return Err(VoteError::NotElector(author));
         }
+
+        self.motion.arguments.push(Argument { author, side, text: text.into() });
+
+        Ok(self.motion.arguments.len() - 1)
+    }
+
+    /// pending amendments, in order of proposal, with the votes for each
+    pub fn amendments(&self) -> impl Iterator<Item = (&Amendment, u64)> {
+        self.stage.amendments.iter().map(|a| (&a.amendment, a.votes))
     }
 
-    pub fn into_referendum(self) -> Result<Procedure<Referendum>, Self> {
-        let half = self.stage.voter_ids.len() as u64 / 2;
+    /// returns the index of the amendment, to be voted on by developers
+    ///
+    /// error and does nothing if `author` is not developper
+    pub fn propose_amendment(
+        &mut self,
+        author: PersonId,
+        amendment: Amendment
+    ) -> Result<usize, VoteError> {
+        if !self.motion.developers.contains(&author) {
+            return Err(VoteError::NotDeveloper(author));
+        }
 
-        if self.stage.approval_votes > half {
-            Ok(Procedure {
-                motion: self.motion,
-                stage: Referendum {
-                    have_voted: Vec::new(),
-                    votes_for: 0,
-                    votes_against: 0
-                }
-            })
+        self.stage.amendments.push(PendingAmendment {
+            amendment,
+            have_voted: HashSet::new(),
+            votes: 0
+        });
+
+        Ok(self.stage.amendments.len() - 1)
+    }
+
+    /// error and does nothing if `person_id` has already voted for the
+    /// amendment or is not developper, or if there is no amendment at `index`
+    pub fn register_amendment_vote(
+        &mut self,
+        person_id: PersonId,
+        index: usize
+    ) -> Result<(), VoteError> {
+        if !self.motion.developers.contains(&person_id) {
+            return Err(VoteError::NotDeveloper(person_id));
+        }
+
+        let Some(pending) = self.stage.amendments.get_mut(index) else {
+            return Err(VoteError::InvalidBallot(person_id));
+        };
+
+        if pending.have_voted.contains(&person_id) {
+            return Err(VoteError::AlreadyVoted(person_id));
+        }
+
+        pending.votes += 1;
+        pending.have_voted.insert(person_id);
+
+        self.notify_vote();
+
+        Ok(())
+    }
+
+    /// replaces the text of the motion with the amendment at `index` if an
+    /// absolute majority of developers has voted for it, recording the change
+    /// in [`Motion::revisions`]. returns whether the amendment was adopted
+    ///
+    /// as they were written against the previous text, all other pending
+    /// amendments are discarded on adoption
+    pub fn adopt_amendment(&mut self, index: usize) -> bool {
+        let needed = absolute_majority(self.motion.dev_count());
+
+        let is_adopted = self.stage.amendments.get(index)
+            .is_some_and(|pending| pending.votes >= needed);
+
+        if is_adopted {
+            let amendment = self.stage.amendments.swap_remove(index).amendment;
+
+            self.stage.amendments.clear();
+
+            let previous_title = std::mem::replace(&mut self.motion.title, amendment.title.clone());
+            let previous_description = std::mem::replace(
+                &mut self.motion.description,
+                amendment.description.clone()
+            );
+
+            self.motion.revisions.push(Revision {
+                previous_title,
+                previous_description,
+                amendment
+            });
+        }
+
+        is_adopted
+    }
+
+    /// returns Err(self) unchanged if an absolute majority of developers has
+    /// not voted to revert
+    ///
+    /// the prototype starts over with no proposal votes
+    pub fn into_prototype(self) -> Result<Procedure<Prototype>, Self> {
+        if self.stage.revert_votes >= absolute_majority(self.motion.dev_count()) {
+            Ok(self.transition(Prototype::new()))
         } else {
             Err(self)
         }
     }
-}
 
-impl Procedure<Referendum> {
-    pub fn votes_for(&self) -> u64 {
-        self.stage.votes_for
+    /// returns Err if proposal end date has not been reached, if the electorate
+    /// is empty, as no petitioner could then approve the motion, or if `ratio`
+    /// is not within (0, 1]
+    ///
+    /// `ratio` is the size of the petitioner group relative to the electorate,
+    /// see [`PETITIONER_RATIO`] for a default. the size is rounded up, so that
+    /// the group has at least one petitioner if the electorate is not empty
+    pub fn into_petition(self, ratio: f32) -> Result<Procedure<Petition>, Self> {
+        self.into_petition_with_rng(ratio, &mut rand::thread_rng())
     }
 
-    pub fn votes_against(&self) -> u64 {
-        self.stage.votes_against
+    /// like [`into_petition`](Self::into_petition), but first blocks the
+    /// current thread until the debate is over, returning immediately if it
+    /// already is
+    ///
+    /// returns Err only if the electorate is empty or `ratio` is not within
+    /// (0, 1]. the clock is checked at least every [`CLOCK_POLL_INTERVAL`],
+    /// so with a [`MockClock`](crate::MockClock) this blocks until the clock
+    /// is advanced past the end date, however far the end date is
+    pub fn wait_and_into_petition(self, ratio: f32) -> Result<Procedure<Petition>, Self> {
+        // negative durations cannot be converted, the debate then being over.
+        while let Ok(remaining) = (self.stage.end_date - self.clock.now()).to_std() {
+            if remaining.is_zero() {
+                break;
+            }
+
+            std::thread::sleep(remaining.min(CLOCK_POLL_INTERVAL));
+        }
+
+        self.into_petition(ratio)
     }
 
-    pub fn register_vote_for(&mut self, person_id: PersonId) -> Result<(), ()> {
-        let is_valid = self.motion.electors.contains(&person_id)
-            && !self.stage.have_voted.contains(&person_id);
+    /// like [`into_petition`](Self::into_petition), drawing petitioners with
+    /// `rng`, which may be seeded for reproducibility
+    pub fn into_petition_with_rng(
+        self,
+        ratio: f32,
+        rng: &mut impl Rng
+    ) -> Result<Procedure<Petition>, Self> {
+        let Some(petitioner_count) = petitioner_count(self.motion.electors.len(), ratio) else {
+            return Err(self);
+        };
 
-        if is_valid {
-            self.stage.votes_for += 1;
-            self.stage.have_voted.push(person_id);
+        self.into_petition_sized(petitioner_count, rng)
+    }
 
-            Ok(())
-        } else {
-            Err(())
-        }
+    /// like [`into_petition`](Self::into_petition), with the size of the
+    /// petitioner group given by [`dynamic_petitioner_count`]
+    pub fn into_dynamic_petition(self) -> Result<Procedure<Petition>, Self> {
+        self.into_dynamic_petition_with_rng(&mut rand::thread_rng())
     }
 
-    pub fn register_vote_against(&mut self, person_id: PersonId) -> Result<(), ()> {
-        let is_valid = self.motion.electors.contains(&person_id)
-            && !self.stage.have_voted.contains(&person_id);
+    /// like [`into_dynamic_petition`](Self::into_dynamic_petition), drawing
+    /// petitioners with `rng`
+    pub fn into_dynamic_petition_with_rng(
+        self,
+        rng: &mut impl Rng
+    ) -> Result<Procedure<Petition>, Self> {
+        let petitioner_count = dynamic_petitioner_count(self.motion.elector_count() as u64);
 
-        if is_valid {
-            self.stage.votes_against += 1;
-            self.stage.have_voted.push(person_id);
+        self.into_petition_sized(petitioner_count as usize, rng)
+    }
+
+    /// like [`into_petition`](Self::into_petition), but petitioners are drawn
+    /// from each stratum of the electorate in proportion to its size, so that
+    /// every group is represented. `strata` gives the stratum of each elector
+    ///
+    /// the petitioner group has the same size as with `into_petition`. seats
+    /// left over after rounding down the share of each stratum go to the
+    /// strata with the largest remainders
+    pub fn into_petition_stratified<K: Eq + Hash>(
+        self,
+        ratio: f32,
+        strata: impl Fn(PersonId) -> K
+    ) -> Result<Procedure<Petition>, Self> {
+        self.into_petition_stratified_with_rng(ratio, strata, &mut rand::thread_rng())
+    }
+
+    /// like [`into_petition_stratified`](Self::into_petition_stratified),
+    /// drawing petitioners with `rng`
+    pub fn into_petition_stratified_with_rng<K: Eq + Hash>(
+        self,
+        ratio: f32,
+        strata: impl Fn(PersonId) -> K,
+        rng: &mut impl Rng
+    ) -> Result<Procedure<Petition>, Self> {
+        let Some(petitioner_count) = petitioner_count(self.motion.electors.len(), ratio) else {
+            return Err(self);
+        };
+
+        self.into_petition_of(|motion| {
+            stratified_sample(&motion.electors, petitioner_count, strata, rng)
+        })
+    }
+
+    fn into_petition_sized(
+        self,
+        petitioner_count: usize,
+        rng: &mut impl Rng
+    ) -> Result<Procedure<Petition>, Self> {
+        self.into_petition_of(|motion| Petition::sample(motion, petitioner_count, rng))
+    }
 
-            Ok(())
+    /// moves to the petition drawn by `draw`, once the debate is over
+    ///
+    /// the petitioner group of a nonempty electorate is never empty, see
+    /// [`petitioner_count`] and [`dynamic_petitioner_count`]
+    fn into_petition_of(
+        self,
+        draw: impl FnOnce(&Motion) -> Petition
+    ) -> Result<Procedure<Petition>, Self> {
+        if self.is_ready_for_petition() {
+            let petition = draw(&self.motion);
+
+            Ok(self.transition(petition))
         } else {
-            Err(())
+            Err(self)
         }
     }
 
-    pub fn pass(self) -> Result<(), Self> {
-        if self.stage.votes_for > self.stage.votes_against {
-            Ok(())
+    /// moves straight to the referendum once the debate is over, open to votes
+    /// for `vote_time`, skipping the petition
+    ///
+    /// the petition protects the electorate from motions backed only by a
+    /// minority, which the fast-track forgoes: it is reserved for
+    /// [`MotionCategory::Emergency`] motions backed by
+    /// [`fast_track_votes_needed`](Self::fast_track_votes_needed) developers,
+    /// and so is unavailable to anonymous motions. returns Err(self) unchanged
+    /// otherwise, or if the debate is not over
+    pub fn into_referendum_fast_track(
+        self,
+        vote_time: Duration
+    ) -> Result<Procedure<Referendum>, Self> {
+        let may_skip = self.motion.category == MotionCategory::Emergency
+            && !self.motion.is_anonymous()
+            && self.fast_track_votes() >= self.fast_track_votes_needed()
+            && self.stage.end_date <= self.clock.now();
+
+        if may_skip {
+            let end_date = self.clock.now() + vote_time;
+            let referendum = Referendum::new(end_date);
+
+            Ok(self.transition(referendum))
         } else {
             Err(self)
         }
     }
 }
 
+/// a petition of `count` electors, drawn from each stratum in proportion to its
+/// size
+fn stratified_sample<K: Eq + Hash>(
+    electors: &[PersonId],
+    count: usize,
+    strata: impl Fn(PersonId) -> K,
+    rng: &mut impl Rng
+) -> Petition {
+    use rand::seq::SliceRandom;
+
+    // strata are kept in order of first appearance so that a seeded `rng`
+    // draws the same petitioners.
+    let mut indices = HashMap::new();
+    let mut groups = Vec::<Vec<PersonId>>::new();
+
+    for &id in electors {
+        let idx = *indices.entry(strata(id)).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+
+        groups[idx].push(id);
+    }
+
+    let total = electors.len().max(1);
+
+    let mut seats = groups.iter()
+        .map(|group| group.len() * count / total)
+        .collect::<Vec<_>>();
+
+    let mut by_remainder = (0..groups.len()).collect::<Vec<_>>();
+
+    // stable, so ties go to the earliest stratum.
+    by_remainder.sort_by_key(|&g| std::cmp::Reverse(groups[g].len() * count % total));
+
+    let left_over = count - seats.iter().sum::<usize>();
+
+    for &g in by_remainder.iter().take(left_over) {
+        seats[g] += 1;
+    }
+
+    let voter_ids = groups.iter()
+        .zip(seats)
+        .flat_map(|(group, n)| group.choose_multiple(rng, n).copied().collect::<Vec<_>>())
+        .collect();
+
+    Petition::new(voter_ids)
+}
+
+impl Petition {
+    /// a petition without votes, whose `petitioner_count` voters are drawn at
+    /// random from the electorate of `motion`
+    fn sample(motion: &Motion, petitioner_count: usize, rng: &mut impl Rng) -> Self {
+        use rand::seq::SliceRandom;
+
+        let voter_ids = motion.electors.choose_multiple(rng, petitioner_count)
+            .copied()
+            .collect::<Vec<_>>();
+
+        Self::new(voter_ids)
+    }
+
+    /// a petition without votes of `voter_ids`, sorted to be searched
+    fn new(mut voter_ids: Vec<PersonId>) -> Self {
+        voter_ids.sort_unstable();
+
+        Self {
+            voter_ids,
+            have_voted: HashSet::new(),
+            proxies: HashMap::new(),
+            approval_votes: 0,
+            vote_times: Vec::new(),
+            round: 1
+        }
+    }
+}
+
+/// the longest a wait for a date sleeps before checking the clock again, so
+/// that it also ends soon after a [`MockClock`](crate::MockClock) is advanced
+/// past the date, see [`Procedure::wait_and_into_petition`]
+pub const CLOCK_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+
+/// the shortest public debate allowed by [`Procedure::into_proposal`], one week
+pub const MIN_PROPOSAL_DURATION: std::time::Duration =
+    std::time::Duration::from_secs(7 * 24 * 60 * 60);
+
+/// the default size of the petitioner group relative to population
+///
+/// in reality this would be a dynamic value, inversely proportional to the size
+/// of the population, see [`dynamic_petitioner_count`]
+pub const PETITIONER_RATIO: f32 = 0.25;
+
+/// scale of the petitioner group relative to the square root of the population
+pub const PETITIONER_SCALE: f64 = 5.0;
+
+/// the smallest petitioner group drawn from a population large enough to allow
+/// it
+pub const MIN_PETITIONERS: u64 = 10;
+
+/// size of the petitioner group for an electorate of `electors` given as a
+/// `ratio` of it, rounded up but never exceeding the electorate
+///
+/// none if `ratio` is not within (0, 1]
+fn petitioner_count(electors: usize, ratio: f32) -> Option<usize> {
+    // also rejects NaN.
+    if !(ratio > 0.0 && ratio <= 1.0) {
+        return None;
+    }
+
+    Some(((electors as f64 * ratio as f64).ceil() as usize).min(electors))
+}
+
+/// size of the petitioner group for a population of `population`
+///
+/// the petitioner ratio is [`PETITIONER_SCALE`] / sqrt(`population`), so the
+/// group grows with the square root of the population instead of linearly,
+/// remaining of manageable size for large electorates. for instance, the group
+/// is the whole of a population of 10, 500 people out of 10 thousand, and about
+/// 16 thousand out of 10 million
+///
+/// the result is at least [`MIN_PETITIONERS`], but never exceeds `population`
+pub fn dynamic_petitioner_count(population: u64) -> u64 {
+    let count = (PETITIONER_SCALE * (population as f64).sqrt()).ceil() as u64;
+
+    count.max(MIN_PETITIONERS).min(population)
+}
+
+impl Procedure<Petition> {
+    pub fn votes_for(&self) -> u64 {
+        self.stage.approval_votes
+    }
+
+    /// the petitioners, in order of ID
+    pub fn voter_ids(&self) -> &[PersonId] {
+        &self.stage.voter_ids
+    }
+
+    /// the time at which each vote was cast, in order
+    ///
+    /// times are not associated with voters, preserving secrecy
+    pub fn vote_times(&self) -> &[DateTime] {
+        &self.stage.vote_times
+    }
+
+    /// only members of the petitioner group may vote, see [`VoteError`]
+    pub fn register_approval_vote(&mut self, person_id: PersonId) -> Result<(), VoteError> {
+        if self.stage.voter_ids.binary_search(&person_id).is_err() {
+            return Err(VoteError::NotPetitioner(person_id));
+        }
+
+        if !self.is_eligible(person_id) {
+            return Err(VoteError::Ineligible(person_id));
+        }
+
+        if self.stage.have_voted.contains(&person_id) {
+            return Err(VoteError::AlreadyVoted(person_id));
+        }
+
+        self.stage.approval_votes += 1;
+        self.stage.have_voted.insert(person_id);
+        self.stage.vote_times.push(self.clock.now());
+
+        self.notify_vote();
+
+        Ok(())
+    }
+
+    /// approval vote cast by `proxy` on behalf of `on_behalf_of`, who may be
+    /// unable to access the ballot themselves. the vote is that of
+    /// `on_behalf_of`, and `proxy` need not be a petitioner
+    pub fn register_approval_vote_as(
+        &mut self,
+        proxy: PersonId,
+        on_behalf_of: PersonId
+    ) -> Result<(), VoteError> {
+        self.register_approval_vote(on_behalf_of)?;
+        self.stage.proxies.insert(on_behalf_of, proxy);
+
+        Ok(())
+    }
+
+    /// the person who voted on behalf of `person_id`, if any
+    pub fn proxy_of(&self, person_id: PersonId) -> Option<PersonId> {
+        self.stage.proxies.get(&person_id).copied()
+    }
+
+    /// the number of approval votes required for the motion to proceed to
+    /// referendum
+    pub fn votes_needed(&self) -> u64 {
+        absolute_majority(self.stage.voter_ids.len())
+    }
+
+    /// fraction of the required approval votes obtained, from 0 to 1
+    pub fn progress(&self) -> f32 {
+        progress(self.stage.approval_votes, self.votes_needed())
+    }
+
+    /// the round of the petition, 1 for the first one drawn after the debate,
+    /// see [`into_next_petition`](Self::into_next_petition)
+    pub fn round(&self) -> u64 {
+        self.stage.round
+    }
+
+    /// draws a new petitioner group of the same size, for instance to give the
+    /// motion another chance after a failed petition. all votes are discarded,
+    /// and the round is unchanged
+    ///
+    /// the new group is drawn independently of the previous one, so it may
+    /// differ from it unless the group spans the whole electorate. it is drawn
+    /// uniformly, even if the previous one was stratified
+    pub fn reroll(self) -> Procedure<Petition> {
+        self.reroll_with_rng(&mut rand::thread_rng())
+    }
+
+    /// like [`reroll`](Self::reroll), drawing petitioners with `rng`
+    pub fn reroll_with_rng(self, rng: &mut impl Rng) -> Procedure<Petition> {
+        let petition = Petition {
+            round: self.stage.round,
+            ..Petition::sample(&self.motion, self.stage.voter_ids.len(), rng)
+        };
+
+        self.transition(petition)
+    }
+
+    /// once the petitioners have approved the motion, draws a new petitioner
+    /// group sized by `ratio` for a further round of approval, as with
+    /// [`into_petition`](Procedure::into_petition)
+    ///
+    /// returns Err(self) unchanged if not enough votes, or if `ratio` is not
+    /// within (0, 1]
+    pub fn into_next_petition(self, ratio: f32) -> Result<Procedure<Petition>, Self> {
+        self.into_next_petition_with_rng(ratio, &mut rand::thread_rng())
+    }
+
+    /// like [`into_next_petition`](Self::into_next_petition), drawing
+    /// petitioners with `rng`
+    pub fn into_next_petition_with_rng(
+        self,
+        ratio: f32,
+        rng: &mut impl Rng
+    ) -> Result<Procedure<Petition>, Self> {
+        let petitioner_count = petitioner_count(self.motion.electors.len(), ratio);
+
+        match petitioner_count {
+            Some(petitioner_count) if self.is_approved() => {
+                let petition = Petition {
+                    round: self.stage.round + 1,
+                    ..Petition::sample(&self.motion, petitioner_count, rng)
+                };
+
+                Ok(self.transition(petition))
+            }
+            _ => Err(self)
+        }
+    }
+
+    /// whether an absolute majority of petitioners approved the motion
+    fn is_approved(&self) -> bool {
+        self.stage.approval_votes >= self.votes_needed()
+    }
+
+    /// returns Err(self) unchanged if not enough votes
+    ///
+    /// the referendum is open to votes for `vote_time`
+    pub fn into_referendum(self, vote_time: Duration) -> Result<Procedure<Referendum>, Self> {
+        if self.is_approved() {
+            let end_date = self.clock.now() + vote_time;
+            let referendum = Referendum::new(end_date);
+
+            Ok(self.transition(referendum))
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl Referendum {
+    /// an empty referendum closing at `end_date`
+    fn new(end_date: DateTime) -> Self {
+        Self {
+            end_date,
+            have_voted: HashMap::new(),
+            delegations: HashMap::new(),
+            proxies: HashMap::new(),
+            votes_for: 0,
+            votes_against: 0,
+            abstentions: 0,
+            spoiled: 0,
+            vote_times: Vec::new(),
+            token_holders: HashSet::new(),
+            unspent_tokens: HashSet::new(),
+            secret_ballots: 0,
+            locks: HashMap::new(),
+            vetoes: Vec::new()
+        }
+    }
+}
+
+impl Procedure<Referendum> {
+    /// the date after which votes are no longer accepted
+    pub fn end_date(&self) -> DateTime {
+        self.stage.end_date
+    }
+
+    /// whether votes are still accepted, until the end date
+    pub fn is_open(&self) -> bool {
+        self.clock.now() < self.stage.end_date
+    }
+
+    /// weighted votes for adoption, including delegated votes
+    pub fn votes_for(&self) -> u64 {
+        self.tally().votes_for
+    }
+
+    /// weighted votes against adoption, including delegated votes
+    pub fn votes_against(&self) -> u64 {
+        self.tally().votes_against
+    }
+
+    pub fn abstentions(&self) -> u64 {
+        self.stage.abstentions
+    }
+
+    /// ballots spoiled, intentionally or not, see
+    /// [`register_spoiled`](Self::register_spoiled)
+    pub fn spoiled(&self) -> u64 {
+        self.stage.spoiled
+    }
+
+    /// weighted votes for and against together with abstentions and spoiled
+    /// ballots, including delegated votes
+    pub fn votes_cast(&self) -> u64 {
+        let tally = self.tally();
+
+        tally.votes_for
+            .saturating_add(tally.votes_against)
+            .saturating_add(self.stage.abstentions)
+            .saturating_add(self.stage.spoiled)
+    }
+
+    /// fraction of electors who cast a ballot, abstentions and spoiled ballots
+    /// included, from 0 to 1. turnout ignores vote weights, and includes
+    /// electors whose delegated vote was cast
+    ///
+    /// 0 if the electorate is empty
+    pub fn turnout(&self) -> f32 {
+        turnout(self.tally().voters, self.motion.electors.len())
+    }
+
+    /// weighted votes for minus weighted votes against, including delegated
+    /// votes. positive when the motion would pass
+    ///
+    /// saturates at the bounds of `i64`
+    pub fn margin(&self) -> i64 {
+        let tally = self.tally();
+        let margin = tally.votes_for as i128 - tally.votes_against as i128;
+
+        margin.clamp(i64::MIN as i128, i64::MAX as i128) as i64
+    }
+
+    /// share of the votes for among votes for and against, from 0 to 100.
+    /// abstentions are not counted
+    ///
+    /// 0 if no votes for or against were cast
+    pub fn percent_for(&self) -> f32 {
+        let tally = self.tally();
+
+        percent(tally.votes_for, tally.votes_for.saturating_add(tally.votes_against))
+    }
+
+    /// see [`percent_for`](Self::percent_for)
+    pub fn percent_against(&self) -> f32 {
+        let tally = self.tally();
+
+        percent(tally.votes_against, tally.votes_for.saturating_add(tally.votes_against))
+    }
+
+    /// the time at which each ballot, abstentions included, was cast, in order
+    ///
+    /// times are not associated with voters, preserving secrecy. changed votes
+    /// keep the time at which they were first cast
+    pub fn vote_times(&self) -> &[DateTime] {
+        &self.stage.vote_times
+    }
+
+    /// equivalent to a vote of weight 1
+    pub fn register_vote_for(&mut self, person_id: PersonId) -> Result<(), VoteError> {
+        self.register_weighted_vote_for(person_id, 1)
+    }
+
+    /// equivalent to a vote of weight 1
+    pub fn register_vote_against(&mut self, person_id: PersonId) -> Result<(), VoteError> {
+        self.register_weighted_vote_against(person_id, 1)
+    }
+
+    /// vote for adoption counting as `weight` votes, for electorates in which
+    /// voters do not carry equal weight
+    ///
+    /// each voter may still only vote once, whatever their weight. error if the
+    /// total weight of votes for and against would overflow, counting a vote
+    /// for each token yet to be spent
+    pub fn register_weighted_vote_for(
+        &mut self,
+        person_id: PersonId,
+        weight: u64
+    ) -> Result<(), VoteError> {
+        self.register_vote(person_id, Ballot { choice: Choice::For, weight })
+    }
+
+    /// see [`register_weighted_vote_for`](Self::register_weighted_vote_for)
+    pub fn register_weighted_vote_against(
+        &mut self,
+        person_id: PersonId,
+        weight: u64
+    ) -> Result<(), VoteError> {
+        self.register_vote(person_id, Ballot { choice: Choice::Against, weight })
+    }
+
+    /// vote for adoption by a voter who commits to lock their participation for
+    /// `lock` after the referendum closes, in exchange for a weight of
+    /// [`conviction_weight`]`(lock)`
+    ///
+    /// the crate only records the lock, see [`unlock_date`](Self::unlock_date),
+    /// leaving its enforcement to the caller. error if `lock` is negative or
+    /// would unlock past the latest representable date, otherwise like
+    /// [`register_weighted_vote_for`](Self::register_weighted_vote_for)
+    pub fn register_vote_for_locked(
+        &mut self,
+        person_id: PersonId,
+        lock: Duration
+    ) -> Result<(), VoteError> {
+        self.register_locked_vote(person_id, Choice::For, lock)
+    }
+
+    /// see [`register_vote_for_locked`](Self::register_vote_for_locked)
+    pub fn register_vote_against_locked(
+        &mut self,
+        person_id: PersonId,
+        lock: Duration
+    ) -> Result<(), VoteError> {
+        self.register_locked_vote(person_id, Choice::Against, lock)
+    }
+
+    fn register_locked_vote(
+        &mut self,
+        person_id: PersonId,
+        choice: Choice,
+        lock: Duration
+    ) -> Result<(), VoteError> {
+        if lock < Duration::zero() {
+            return Err(VoteError::InvalidBallot(person_id));
+        }
+
+        let unlock_date = self.stage.end_date
+            .checked_add_signed(lock)
+            .ok_or(VoteError::InvalidBallot(person_id))?;
+
+        self.register_vote(person_id, Ballot { choice, weight: conviction_weight(lock) })?;
+        self.stage.locks.insert(person_id, unlock_date);
+
+        Ok(())
+    }
+
+    /// the date until which `person_id` has locked their participation, if
+    /// they voted with a lock
+    pub fn unlock_date(&self, person_id: PersonId) -> Option<DateTime> {
+        self.stage.locks.get(&person_id).copied()
+    }
+
+    /// registers each vote, for adoption if true, with a weight of 1
+    ///
+    /// the whole batch is processed even if some votes are rejected. the result
+    /// of each vote is returned in order
+    pub fn register_votes(
+        &mut self,
+        votes: impl IntoIterator<Item = (PersonId, bool)>
+    ) -> Vec<Result<(), VoteError>> {
+        votes.into_iter()
+            .map(|(person_id, vote_for)| if vote_for {
+                self.register_vote_for(person_id)
+            } else {
+                self.register_vote_against(person_id)
+            })
+            .collect()
+    }
+
+    /// vote for adoption cast by `proxy` on behalf of `on_behalf_of`, who may
+    /// be unable to access the ballot themselves. the vote is that of
+    /// `on_behalf_of`, and `proxy` need not be an elector
+    pub fn register_vote_for_as(
+        &mut self,
+        proxy: PersonId,
+        on_behalf_of: PersonId
+    ) -> Result<(), VoteError> {
+        self.register_vote_for(on_behalf_of)?;
+        self.stage.proxies.insert(on_behalf_of, proxy);
+
+        Ok(())
+    }
+
+    /// see [`register_vote_for_as`](Self::register_vote_for_as)
+    pub fn register_vote_against_as(
+        &mut self,
+        proxy: PersonId,
+        on_behalf_of: PersonId
+    ) -> Result<(), VoteError> {
+        self.register_vote_against(on_behalf_of)?;
+        self.stage.proxies.insert(on_behalf_of, proxy);
+
+        Ok(())
+    }
+
+    /// the person who voted on behalf of `person_id`, if any
+    pub fn proxy_of(&self, person_id: PersonId) -> Option<PersonId> {
+        self.stage.proxies.get(&person_id).copied()
+    }
+
+    /// records `person_id` as having participated without voting for or
+    /// against. an abstention is final and cannot later be changed
+    pub fn register_abstention(&mut self, person_id: PersonId) -> Result<(), VoteError> {
+        self.register_vote(person_id, Ballot { choice: Choice::Abstain, weight: 1 })
+    }
+
+    /// records the ballot of `person_id` as spoiled, counting as participation
+    /// but reported apart from abstentions. like an abstention, a spoiled
+    /// ballot is final
+    pub fn register_spoiled(&mut self, person_id: PersonId) -> Result<(), VoteError> {
+        self.register_vote(person_id, Ballot { choice: Choice::Spoiled, weight: 1 })
+    }
+
+    /// delegates the vote of `from` to `to`, replacing any previous delegation
+    /// by `from`
+    ///
+    /// if `from` does not vote, their vote counts with a weight of 1 for the
+    /// choice of `to`, or of whomever `to` has delegated to in turn. voting
+    /// directly overrides the delegation
+    ///
+    /// error and does nothing if either person is not an elector, if `from`
+    /// has already voted or been issued a token, or if the delegation would
+    /// form a cycle
+    pub fn delegate(&mut self, from: PersonId, to: PersonId) -> Result<(), VoteError> {
+        if !self.is_open() {
+            return Err(VoteError::Closed);
+        }
+
+        for id in [from, to] {
+            if !self.motion.is_elector(id) {
+                return Err(VoteError::NotElector(id));
+            }
+        }
+
+        if !self.is_eligible(from) {
+            return Err(VoteError::Ineligible(from));
+        }
+
+        self.check_not_voted(from)?;
+
+        let mut delegate = Some(to);
+
+        while let Some(id) = delegate {
+            if id == from {
+                return Err(VoteError::DelegationCycle(from));
+            }
+
+            delegate = self.stage.delegations.get(&id).copied();
+        }
+
+        self.stage.delegations.insert(from, to);
+
+        Ok(())
+    }
+
+    /// the person to whom `person_id` has delegated their vote, if any
+    pub fn delegate_of(&self, person_id: PersonId) -> Option<PersonId> {
+        self.stage.delegations.get(&person_id).copied()
+    }
+
+    /// replaces the vote previously cast by `person_id`
+    ///
+    /// error and does nothing if `person_id` is not an elector, has not yet
+    /// voted, or has abstained or spoiled their ballot. changing a vote to the
+    /// same choice has no effect. the weight of the vote is kept
+    pub fn change_vote(&mut self, person_id: PersonId, vote_for: bool) -> Result<(), VoteError> {
+        if !self.is_open() {
+            return Err(VoteError::Closed);
+        }
+
+        if !self.motion.is_elector(person_id) {
+            return Err(VoteError::NotElector(person_id));
+        }
+
+        let Some(ballot) = self.stage.have_voted.get_mut(&person_id) else {
+            return Err(VoteError::NotVoted(person_id));
+        };
+
+        let new_choice = if vote_for { Choice::For } else { Choice::Against };
+
+        match (ballot.choice, new_choice) {
+            (Choice::Abstain | Choice::Spoiled, _) => {
+                return Err(VoteError::AlreadyVoted(person_id))
+            }
+            (old, new) if old == new => return Ok(()),
+            _ => ()
+        }
+
+        ballot.choice = new_choice;
+
+        if vote_for {
+            self.stage.votes_against -= ballot.weight;
+            self.stage.votes_for += ballot.weight;
+        } else {
+            self.stage.votes_for -= ballot.weight;
+            self.stage.votes_against += ballot.weight;
+        }
+
+        self.notify_vote();
+
+        Ok(())
+    }
+
+    fn register_vote(&mut self, person_id: PersonId, ballot: Ballot) -> Result<(), VoteError> {
+        if !self.is_open() {
+            return Err(VoteError::Closed);
+        }
+
+        if !self.motion.is_elector(person_id) {
+            return Err(VoteError::NotElector(person_id));
+        }
+
+        if !self.is_eligible(person_id) {
+            return Err(VoteError::Ineligible(person_id));
+        }
+
+        self.check_not_voted(person_id)?;
+
+        if ballot.weight == 0 {
+            return Err(VoteError::ZeroWeight(person_id));
+        }
+
+        let total = self.reserved_total()
+            .and_then(|total| total.checked_add(ballot.weight));
+
+        if total.is_none() {
+            return Err(VoteError::Overflow(person_id));
+        }
+
+        match ballot.choice {
+            Choice::For => self.stage.votes_for += ballot.weight,
+            Choice::Against => self.stage.votes_against += ballot.weight,
+            Choice::Abstain => self.stage.abstentions += 1,
+            Choice::Spoiled => self.stage.spoiled += 1
+        }
+
+        self.stage.have_voted.insert(person_id, ballot);
+        self.stage.vote_times.push(self.clock.now());
+
+        self.notify_vote();
+
+        Ok(())
+    }
+
+    /// a token with which `person_id` may vote in secret, see
+    /// [`register_secret_vote`](Self::register_secret_vote)
+    ///
+    /// the token is random, and the procedure keeps it apart from its holder:
+    /// it only records that `person_id` was issued a token, and not which, so
+    /// a ballot cast with it cannot be traced back. the holder may then only
+    /// vote with the token, and their delegation, if any, is withdrawn. a token
+    /// is issued only once, so it must be handed to the elector in private
+    ///
+    /// error and does nothing if voting has closed, if `person_id` is not an
+    /// eligible elector, if they have already voted or been issued a token, or
+    /// if weighted votes leave no room in the tally for the ballot of the token
+    pub fn issue_token(&mut self, person_id: PersonId) -> Result<BallotToken, VoteError> {
+        if !self.is_open() {
+            return Err(VoteError::Closed);
+        }
+
+        if !self.motion.is_elector(person_id) {
+            return Err(VoteError::NotElector(person_id));
+        }
+
+        if !self.is_eligible(person_id) {
+            return Err(VoteError::Ineligible(person_id));
+        }
+
+        self.check_not_voted(person_id)?;
+
+        if self.reserved_total().and_then(|total| total.checked_add(1)).is_none() {
+            return Err(VoteError::Overflow(person_id));
+        }
+
+        let token = BallotToken::random(&mut rand::thread_rng());
+
+        self.stage.token_holders.insert(person_id);
+        self.stage.unspent_tokens.insert(token);
+        self.stage.delegations.remove(&person_id);
+
+        Ok(token)
+    }
+
+    /// vote with a weight of 1 by the holder of `token`, without recording
+    /// their identity. the vote cannot be changed, and overrides any
+    /// delegation by the holder
+    ///
+    /// error and does nothing if `token` was not issued by this referendum, or
+    /// if it has already been spent
+    pub fn register_secret_vote(
+        &mut self,
+        token: BallotToken,
+        vote_for: bool
+    ) -> Result<(), VoteError> {
+        let choice = if vote_for { Choice::For } else { Choice::Against };
+
+        self.register_secret_ballot(token, choice)
+    }
+
+    /// see [`register_secret_vote`](Self::register_secret_vote)
+    pub fn register_secret_abstention(&mut self, token: BallotToken) -> Result<(), VoteError> {
+        self.register_secret_ballot(token, Choice::Abstain)
+    }
+
+    fn register_secret_ballot(
+        &mut self,
+        token: BallotToken,
+        choice: Choice
+    ) -> Result<(), VoteError> {
+        if !self.is_open() {
+            return Err(VoteError::Closed);
+        }
+
+        if !self.stage.unspent_tokens.contains(&token) {
+            return Err(VoteError::InvalidToken);
+        }
+
+        let count = match choice {
+            Choice::For => &mut self.stage.votes_for,
+            Choice::Against => &mut self.stage.votes_against,
+            Choice::Abstain => &mut self.stage.abstentions,
+            Choice::Spoiled => &mut self.stage.spoiled
+        };
+
+        // room was reserved in the tally when the token was issued.
+        *count += 1;
+
+        self.stage.unspent_tokens.remove(&token);
+        self.stage.secret_ballots += 1;
+        self.stage.vote_times.push(self.clock.now());
+
+        self.notify_vote();
+
+        Ok(())
+    }
+
+    /// the votes for and against together with a vote for each unspent token,
+    /// or `None` if it overflows
+    ///
+    /// bounding this sum rather than each count keeps changed votes and token
+    /// ballots from overflowing either
+    fn reserved_total(&self) -> Option<u64> {
+        self.stage.votes_for
+            .checked_add(self.stage.votes_against)?
+            .checked_add(self.stage.unspent_tokens.len() as u64)
+    }
+
+    /// error if `person_id` has voted directly, or was issued a token with
+    /// which they may have voted
+    fn check_not_voted(&self, person_id: PersonId) -> Result<(), VoteError> {
+        if self.stage.have_voted.contains_key(&person_id) {
+            return Err(VoteError::AlreadyVoted(person_id));
+        }
+
+        if self.stage.token_holders.contains(&person_id) {
+            return Err(VoteError::TokenIssued(person_id));
+        }
+
+        Ok(())
+    }
+
+    /// the number of votes for adoption required to pass the motion given the
+    /// current votes against, as a total including votes already cast
+    pub fn votes_needed_to_pass(&self) -> u64 {
+        self.tally().votes_against.saturating_add(1)
+    }
+
+    /// fraction of the votes for needed to overtake the votes against
+    /// obtained, from 0 to 1
+    pub fn progress(&self) -> f32 {
+        let tally = self.tally();
+
+        progress(tally.votes_for, tally.votes_against.saturating_add(1))
+    }
+
+    /// the direct tallies together with the votes of delegating electors who
+    /// have not voted themselves
+    fn tally(&self) -> Tally {
+        let mut tally = Tally {
+            votes_for: self.stage.votes_for,
+            votes_against: self.stage.votes_against,
+            voters: self.stage.have_voted.len() as u64 + self.stage.secret_ballots
+        };
+
+        // token holders have no delegation.
+        let delegators = self.stage.delegations.keys()
+            .filter(|&id| !self.stage.have_voted.contains_key(id));
+
+        for &delegator in delegators {
+            let choice = self.effective_choice(delegator);
+
+            match choice {
+                // delegated votes saturate rather than failing the whole tally.
+                Some(Choice::For) => tally.votes_for = tally.votes_for.saturating_add(1),
+                Some(Choice::Against) => {
+                    tally.votes_against = tally.votes_against.saturating_add(1)
+                }
+                Some(Choice::Abstain | Choice::Spoiled) | None => ()
+            }
+
+            if choice.is_some() {
+                tally.voters += 1;
+            }
+        }
+
+        tally
+    }
+
+    /// the choice of `person_id` if they voted, or of the first person down
+    /// their chain of delegation who did. votes cast by token are unknown
+    fn effective_choice(&self, person_id: PersonId) -> Option<Choice> {
+        let mut id = person_id;
+
+        // delegations are acyclic, so the chain ends.
+        loop {
+            if let Some(ballot) = self.stage.have_voted.get(&id) {
+                return Some(ballot.choice);
+            }
+
+            id = *self.stage.delegations.get(&id)?;
+        }
+    }
+
+    /// `Some(true)` if the motion is bound to pass, `Some(false)` if it is bound
+    /// to be rejected, whatever the votes of the electors who have not yet
+    /// voted, or `None` if the outcome is still open. once voting has closed,
+    /// the outcome is always decided
+    ///
+    /// remaining electors are assumed to vote with a weight of 1, and ballots
+    /// already cast to be final, so a weighted or changed vote may still
+    /// overturn a decided outcome while voting is open
+    pub fn is_decided(&self) -> Option<bool> {
+        if self.is_vetoed() {
+            return Some(false);
+        }
+
+        let tally = self.tally();
+
+        if !self.is_open() {
+            return Some(tally.votes_for > tally.votes_against);
+        }
+
+        let remaining = (self.motion.electors.len() as u64).saturating_sub(tally.voters);
+
+        if tally.votes_for > tally.votes_against.saturating_add(remaining) {
+            Some(true)
+        } else if tally.votes_for.saturating_add(remaining) <= tally.votes_against {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    /// like [`pass`](Self::pass), but returns Err(self) unchanged while voting
+    /// is still open
+    pub fn pass_after_close(self) -> Result<Procedure<Passed>, Self> {
+        if self.is_open() {
+            Err(self)
+        } else {
+            self.pass()
+        }
+    }
+
+    pub fn pass(self) -> Result<Procedure<Passed>, Self> {
+        self.pass_with_quorum(0.0).map_err(PassError::into_procedure)
+    }
+
+    /// concludes the referendum, keeping only how it resolved. the outcome is
+    /// the same as with [`pass`](Self::pass)
+    pub fn finalize(self) -> Outcome {
+        self.outcome()
+    }
+
+    /// vetoes the motion on behalf of `person_id`, who must be listed in
+    /// [`Motion::vetoers`]. a vetoed motion is rejected whatever the tally, by
+    /// every way of passing or concluding the referendum
+    ///
+    /// vetoes are accepted until the referendum is concluded, even once voting
+    /// has closed. error and does nothing if `person_id` may not veto or has
+    /// already vetoed
+    pub fn register_veto(&mut self, person_id: PersonId) -> Result<(), VoteError> {
+        if !self.motion.vetoers.contains(&person_id) {
+            return Err(VoteError::NotVetoer(person_id));
+        }
+
+        if self.stage.vetoes.contains(&person_id) {
+            return Err(VoteError::AlreadyVoted(person_id));
+        }
+
+        self.stage.vetoes.push(person_id);
+
+        Ok(())
+    }
+
+    /// vetoers who have vetoed the motion, in order
+    pub fn vetoes(&self) -> &[PersonId] {
+        &self.stage.vetoes
+    }
+
+    pub fn is_vetoed(&self) -> bool {
+        !self.stage.vetoes.is_empty()
+    }
+
+    /// the current results as CSV, with a `field,value` header and a row for
+    /// each of the votes for and against, abstentions, spoiled ballots,
+    /// turnout, and outcome if concluded now
+    ///
+    /// only totals are included, preserving secrecy
+    pub fn results_csv(&self) -> String {
+        let tally = self.tally();
+        let outcome = self.outcome();
+
+        format!(
+            "field,value\n\
+             votes_for,{}\n\
+             votes_against,{}\n\
+             abstentions,{}\n\
+             spoiled,{}\n\
+             turnout,{}\n\
+             outcome,{}\n",
+            tally.votes_for,
+            tally.votes_against,
+            self.stage.abstentions,
+            self.stage.spoiled,
+            turnout(tally.voters, self.motion.electors.len()),
+            if outcome.is_passed() { "passed" } else { "rejected" }
+        )
+    }
+
+    /// like [`finalize`](Self::finalize), but the result is inconclusive if
+    /// the [`turnout`](Self::turnout) is below `min_turnout`, between 0 and 1
+    ///
+    /// unlike with [`pass_with_quorum`](Self::pass_with_quorum), a low turnout
+    /// is a result in its own right rather than a failure to pass
+    pub fn finalize_with_min_turnout(self, min_turnout: f32) -> ReferendumResult {
+        let turnout = self.turnout();
+
+        if turnout < min_turnout {
+            ReferendumResult::Inconclusive { turnout }
+        } else {
+            ReferendumResult::Decided(self.outcome())
+        }
+    }
+
+    /// how the referendum would resolve if it were concluded now
+    fn outcome(&self) -> Outcome {
+        let tally = self.tally();
+
+        if self.is_vetoed() {
+            Outcome::Rejected {
+                for_votes: tally.votes_for,
+                against_votes: tally.votes_against,
+                vetoed: true
+            }
+        } else {
+            Outcome::new(tally.votes_for, tally.votes_against)
+        }
+    }
+
+    /// passes if the fraction of for votes among for and against votes is at
+    /// least `ratio`, e.g. 2/3 for a supermajority. abstentions are not counted
+    ///
+    /// as the comparison is inclusive, an even split passes with a ratio of
+    /// 0.5, unlike with [`pass`](Self::pass). a referendum in which no votes for
+    /// or against were cast is always rejected
+    pub fn pass_with_threshold(self, ratio: f32) -> Result<Procedure<Passed>, Self> {
+        if self.is_vetoed() {
+            return Err(self);
+        }
+
+        let tally = self.tally();
+        let cast = tally.votes_for.saturating_add(tally.votes_against);
+
+        if cast > 0 && tally.votes_for as f32 / cast as f32 >= ratio {
+            Ok(self.transition(Passed))
+        } else {
+            Err(self)
+        }
+    }
+
+    /// passes with the majority required by the category of the motion, see
+    /// [`MotionCategory::threshold`](crate::MotionCategory::threshold)
+    pub fn pass_by_category(self) -> Result<Procedure<Passed>, Self> {
+        match self.motion.category.threshold() {
+            Some(ratio) => self.pass_with_threshold(ratio),
+            None => self.pass()
+        }
+    }
+
+    /// like [`pass`](Self::pass), but an even split is resolved by `rule`
+    /// instead of being rejected
+    ///
+    /// a referendum in which no votes for or against were cast is always
+    /// rejected
+    pub fn pass_with_tiebreak(self, rule: TieBreak) -> Result<Procedure<Passed>, Self> {
+        use std::cmp::Ordering;
+
+        if self.is_vetoed() {
+            return Err(self);
+        }
+
+        let tally = self.tally();
+
+        let is_passed = match tally.votes_for.cmp(&tally.votes_against) {
+            Ordering::Greater => true,
+            Ordering::Less => false,
+            Ordering::Equal if tally.votes_for == 0 => false,
+            Ordering::Equal => match rule {
+                TieBreak::RejectOnTie => false,
+                TieBreak::PassOnTie => true,
+                TieBreak::CastingVote(id) => self.effective_choice(id) == Some(Choice::For)
+            }
+        };
+
+        if is_passed {
+            Ok(self.transition(Passed))
+        } else {
+            Err(self)
+        }
+    }
+
+    /// like [`pass`](Self::pass), but additionally requires that the fraction
+    /// of electors who cast a ballot (abstentions included) be at least
+    /// `min_turnout`, between 0 and 1, see [`turnout`](Self::turnout)
+    ///
+    /// the petition stage is meant to obviate a quorum (see [`Procedure`]), but
+    /// some bodies require one regardless. the returned error indicates whether
+    /// the quorum or the majority was lacking
+    pub fn pass_with_quorum(self, min_turnout: f32) -> Result<Procedure<Passed>, PassError> {
+        let tally = self.tally();
+
+        if turnout(tally.voters, self.motion.electors.len()) < min_turnout {
+            Err(PassError::QuorumNotMet(self))
+        } else if self.outcome().is_passed() {
+            Ok(self.transition(Passed))
+        } else {
+            Err(PassError::Rejected(self))
+        }
+    }
+}
+
+/// the highest weight obtainable by locking a vote, see [`conviction_weight`]
+pub const MAX_CONVICTION: u64 = 6;
+
+/// the weight of a vote locked for `lock`: 1, plus 1 for each whole week
+/// locked, up to [`MAX_CONVICTION`]
+///
+/// an unlocked vote thus weighs the same as an ordinary one, and a lock of
+/// five weeks or more gives the highest weight
+pub fn conviction_weight(lock: Duration) -> u64 {
+    (lock.num_weeks().max(0) as u64 + 1).min(MAX_CONVICTION)
+}
+
+/// fraction of `needed` reached by `votes`, capped at 1
+fn progress(votes: u64, needed: u64) -> f32 {
+    (votes as f32 / needed as f32).min(1.0)
+}
+
+/// fraction of an electorate of `electors` represented by `voters`, or 0 if
+/// the electorate is empty
+fn turnout(voters: u64, electors: usize) -> f32 {
+    match electors {
+        0 => 0.0,
+        n => voters as f32 / n as f32
+    }
+}
+
+/// `part` as a percentage of `whole`, or 0 if `whole` is 0
+fn percent(part: u64, whole: u64) -> f32 {
+    match whole {
+        0 => 0.0,
+        _ => part as f32 / whole as f32 * 100.0
+    }
+}
+
+/// the smallest absolute majority of a group of `n` people
+fn absolute_majority(n: usize) -> u64 {
+    n as u64 / 2 + 1
+}
+
+/// at least two thirds of `n`
+fn supermajority(n: usize) -> u64 {
+    (2 * n as u64).div_ceil(3)
+}
+
 mod sealed {
     pub trait Sealed {}
 
@@ -298,4 +2196,5 @@ mod sealed {
     impl Sealed for super::Proposal {}
     impl Sealed for super::Petition {}
     impl Sealed for super::Referendum {}
+    impl Sealed for super::Passed {}
 }
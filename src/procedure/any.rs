@@ -0,0 +1,295 @@
+//! runtime-dispatched electoral procedures
+
+use crate::{Motion, PersonId};
+use super::{
+    Procedure,
+    Prototype,
+    Proposal,
+    Petition,
+    Referendum,
+    RankedReferendum,
+    CommitRevealReferendum,
+    SecretReferendum,
+    QuadraticReferendum,
+    DistrictedReferendum,
+    DistrictId,
+    IssuedTokens,
+    Passed,
+    Retired,
+    StageName
+};
+
+use chrono::Duration;
+
+/// an electoral procedure at a stage only known at runtime
+///
+/// this allows procedures at different stages to be stored together, and
+/// would be the basis for restoring persisted procedures, whose stage is only
+/// known once read. the typestate can be recovered by matching
+#[derive(Debug)]
+pub enum ProcedureAny {
+    Prototype(Procedure<Prototype>),
+    Proposal(Procedure<Proposal>),
+    Petition(Procedure<Petition>),
+    Referendum(Procedure<Referendum>),
+    RankedReferendum(Procedure<RankedReferendum>),
+    CommitRevealReferendum(Procedure<CommitRevealReferendum>),
+    SecretReferendum(Procedure<SecretReferendum>),
+    QuadraticReferendum(Procedure<QuadraticReferendum>),
+    DistrictedReferendum(Procedure<DistrictedReferendum>),
+    Passed(Procedure<Passed>),
+    Retired(Procedure<Retired>)
+}
+
+impl ProcedureAny {
+    pub fn motion(&self) -> &Motion {
+        match self {
+            Self::Prototype(p) => p.motion(),
+            Self::Proposal(p) => p.motion(),
+            Self::Petition(p) => p.motion(),
+            Self::Referendum(p) => p.motion(),
+            Self::RankedReferendum(p) => p.motion(),
+            Self::CommitRevealReferendum(p) => p.motion(),
+            Self::SecretReferendum(p) => p.motion(),
+            Self::QuadraticReferendum(p) => p.motion(),
+            Self::DistrictedReferendum(p) => p.motion(),
+            Self::Passed(p) => p.motion(),
+            Self::Retired(p) => p.motion()
+        }
+    }
+
+    /// the current stage, see [`stage_name`](Self::stage_name) for its name
+    pub fn stage(&self) -> StageName {
+        match self {
+            Self::Prototype(p) => p.stage_name(),
+            Self::Proposal(p) => p.stage_name(),
+            Self::Petition(p) => p.stage_name(),
+            Self::Referendum(p) => p.stage_name(),
+            Self::RankedReferendum(p) => p.stage_name(),
+            Self::CommitRevealReferendum(p) => p.stage_name(),
+            Self::SecretReferendum(p) => p.stage_name(),
+            Self::QuadraticReferendum(p) => p.stage_name(),
+            Self::DistrictedReferendum(p) => p.stage_name(),
+            Self::Passed(p) => p.stage_name(),
+            Self::Retired(p) => p.stage_name()
+        }
+    }
+
+    /// the name of the current stage, such as "petition", see
+    /// [`StageName::as_str`]
+    pub fn stage_name(&self) -> &'static str {
+        self.stage().as_str()
+    }
+
+    // transitions return Err(self) unchanged if the procedure is not at the
+    // stage they start from, or if the transition itself fails
+
+    /// see [`Procedure::into_proposal`]
+    pub fn into_proposal(self, prop_time: Duration) -> Result<Self, Self> {
+        match self {
+            Self::Prototype(p) => p.into_proposal(prop_time).map(Self::from).map_err(Self::from),
+            other => Err(other)
+        }
+    }
+
+    /// see [`Procedure::into_prototype`]
+    pub fn into_prototype(self) -> Result<Self, Self> {
+        match self {
+            Self::Proposal(p) => p.into_prototype().map(Self::from).map_err(Self::from),
+            other => Err(other)
+        }
+    }
+
+    /// see [`Procedure::into_petition`]
+    pub fn into_petition(self, ratio: f32) -> Result<Self, Self> {
+        match self {
+            Self::Proposal(p) => p.into_petition(ratio).map(Self::from).map_err(Self::from),
+            other => Err(other)
+        }
+    }
+
+    /// see [`Procedure::into_next_petition`]
+    pub fn into_next_petition(self, ratio: f32) -> Result<Self, Self> {
+        match self {
+            Self::Petition(p) => p.into_next_petition(ratio).map(Self::from).map_err(Self::from),
+            other => Err(other)
+        }
+    }
+
+    /// see [`Procedure::into_referendum`]
+    pub fn into_referendum(self, vote_time: Duration) -> Result<Self, Self> {
+        match self {
+            Self::Petition(p) => p.into_referendum(vote_time).map(Self::from).map_err(Self::from),
+            other => Err(other)
+        }
+    }
+
+    /// see [`Procedure::into_referendum_fast_track`]
+    pub fn into_referendum_fast_track(self, vote_time: Duration) -> Result<Self, Self> {
+        match self {
+            Self::Prototype(p) => p.into_referendum_fast_track(vote_time)
+                .map(Self::from)
+                .map_err(Self::from),
+            Self::Proposal(p) => p.into_referendum_fast_track(vote_time)
+                .map(Self::from)
+                .map_err(Self::from),
+            other => Err(other)
+        }
+    }
+
+    /// see [`Procedure::into_ranked_referendum`]
+    pub fn into_ranked_referendum(self, options: Vec<String>) -> Result<Self, Self> {
+        match self {
+            Self::Petition(p) => p.into_ranked_referendum(options)
+                .map(Self::from)
+                .map_err(Self::from),
+            other => Err(other)
+        }
+    }
+
+    /// see [`Procedure::into_commit_reveal_referendum`]
+    pub fn into_commit_reveal_referendum(
+        self,
+        commit_time: Duration,
+        reveal_time: Duration
+    ) -> Result<Self, Self> {
+        match self {
+            Self::Petition(p) => p.into_commit_reveal_referendum(commit_time, reveal_time)
+                .map(Self::from)
+                .map_err(Self::from),
+            other => Err(other)
+        }
+    }
+
+    /// see [`Procedure::into_secret_referendum`]
+    pub fn into_secret_referendum(
+        self,
+        vote_time: Duration
+    ) -> Result<(Self, IssuedTokens), Self> {
+        match self {
+            Self::Petition(p) => p.into_secret_referendum(vote_time)
+                .map(|(p, tokens)| (Self::from(p), tokens))
+                .map_err(Self::from),
+            other => Err(other)
+        }
+    }
+
+    /// see [`Procedure::into_quadratic_referendum`]
+    pub fn into_quadratic_referendum(self, vote_time: Duration, budget: u64) -> Result<Self, Self> {
+        match self {
+            Self::Petition(p) => p.into_quadratic_referendum(vote_time, budget)
+                .map(Self::from)
+                .map_err(Self::from),
+            other => Err(other)
+        }
+    }
+
+    /// see [`Procedure::into_districted_referendum`]
+    pub fn into_districted_referendum(
+        self,
+        vote_time: Duration,
+        district: impl Fn(PersonId) -> DistrictId
+    ) -> Result<Self, Self> {
+        match self {
+            Self::Petition(p) => p.into_districted_referendum(vote_time, district)
+                .map(Self::from)
+                .map_err(Self::from),
+            other => Err(other)
+        }
+    }
+
+    /// retires the procedure, see [`Retired`]. procedures which have concluded
+    /// cannot be retired
+    pub fn into_retired(self, reason: impl Into<String>) -> Result<Self, Self> {
+        match self {
+            Self::Prototype(p) => p.into_retired(reason).map(Self::from).map_err(Self::from),
+            Self::Proposal(p) => Ok(p.into_retired(reason).into()),
+            Self::Petition(p) => Ok(p.into_retired(reason).into()),
+            Self::Referendum(p) => Ok(p.into_retired(reason).into()),
+            Self::RankedReferendum(p) => Ok(p.into_retired(reason).into()),
+            Self::CommitRevealReferendum(p) => Ok(p.into_retired(reason).into()),
+            Self::SecretReferendum(p) => Ok(p.into_retired(reason).into()),
+            Self::QuadraticReferendum(p) => Ok(p.into_retired(reason).into()),
+            Self::DistrictedReferendum(p) => Ok(p.into_retired(reason).into()),
+            other @ (Self::Passed(_) | Self::Retired(_)) => Err(other)
+        }
+    }
+
+    /// see [`Procedure::pass`]
+    pub fn pass(self) -> Result<Self, Self> {
+        match self {
+            Self::Referendum(p) => p.pass().map(Self::from).map_err(Self::from),
+            Self::CommitRevealReferendum(p) => p.pass().map(Self::from).map_err(Self::from),
+            Self::SecretReferendum(p) => p.pass().map(Self::from).map_err(Self::from),
+            Self::QuadraticReferendum(p) => p.pass().map(Self::from).map_err(Self::from),
+            Self::DistrictedReferendum(p) => p.pass().map(Self::from).map_err(Self::from),
+            other => Err(other)
+        }
+    }
+}
+
+impl From<Procedure<Prototype>> for ProcedureAny {
+    fn from(p: Procedure<Prototype>) -> Self {
+        Self::Prototype(p)
+    }
+}
+
+impl From<Procedure<Proposal>> for ProcedureAny {
+    fn from(p: Procedure<Proposal>) -> Self {
+        Self::Proposal(p)
+    }
+}
+
+impl From<Procedure<Petition>> for ProcedureAny {
+    fn from(p: Procedure<Petition>) -> Self {
+        Self::Petition(p)
+    }
+}
+
+impl From<Procedure<Referendum>> for ProcedureAny {
+    fn from(p: Procedure<Referendum>) -> Self {
+        Self::Referendum(p)
+    }
+}
+
+impl From<Procedure<RankedReferendum>> for ProcedureAny {
+    fn from(p: Procedure<RankedReferendum>) -> Self {
+        Self::RankedReferendum(p)
+    }
+}
+
+impl From<Procedure<CommitRevealReferendum>> for ProcedureAny {
+    fn from(p: Procedure<CommitRevealReferendum>) -> Self {
+        Self::CommitRevealReferendum(p)
+    }
+}
+
+impl From<Procedure<SecretReferendum>> for ProcedureAny {
+    fn from(p: Procedure<SecretReferendum>) -> Self {
+        Self::SecretReferendum(p)
+    }
+}
+
+impl From<Procedure<QuadraticReferendum>> for ProcedureAny {
+    fn from(p: Procedure<QuadraticReferendum>) -> Self {
+        Self::QuadraticReferendum(p)
+    }
+}
+
+impl From<Procedure<DistrictedReferendum>> for ProcedureAny {
+    fn from(p: Procedure<DistrictedReferendum>) -> Self {
+        Self::DistrictedReferendum(p)
+    }
+}
+
+impl From<Procedure<Passed>> for ProcedureAny {
+    fn from(p: Procedure<Passed>) -> Self {
+        Self::Passed(p)
+    }
+}
+
+impl From<Procedure<Retired>> for ProcedureAny {
+    fn from(p: Procedure<Retired>) -> Self {
+        Self::Retired(p)
+    }
+}
@@ -0,0 +1,209 @@
+//! referendums in which votes are committed in secret before being revealed
+
+use crate::PersonId;
+use super::{
+    Procedure,
+    ProcedureStage,
+    StageName,
+    Petition,
+    Passed,
+    VoteError,
+    DateTime,
+    sealed
+};
+
+use chrono::Duration;
+
+use sha2::{Digest, Sha256};
+
+use std::collections::{HashMap, HashSet};
+
+/// variant of [`Referendum`](super::Referendum) held in two phases, so that no
+/// partial tally can influence late voters
+///
+/// until the commit date, electors commit to their vote by submitting its
+/// [`commitment`]. until the reveal date, they then reveal the vote, which is
+/// counted if it matches the commitment. commitments left unrevealed are
+/// discarded
+#[derive(Clone, Debug)]
+pub struct CommitRevealReferendum {
+    commit_end_date: DateTime,
+    reveal_end_date: DateTime,
+    commitments: HashMap<PersonId, [u8; 32]>,
+    have_revealed: HashSet<PersonId>,
+    votes_for: u64,
+    votes_against: u64
+}
+
+impl ProcedureStage for CommitRevealReferendum {
+    const NAME: StageName = StageName::CommitRevealReferendum;
+}
+impl sealed::Sealed for CommitRevealReferendum {}
+
+/// the commitment to a vote for adoption if `vote_for`, hiding it behind
+/// `nonce`, which should be random and kept secret until the vote is revealed
+///
+/// the commitment is the SHA-256 hash of `vote_for` as a byte, followed by
+/// `nonce`
+pub fn commitment(vote_for: bool, nonce: &[u8]) -> [u8; 32] {
+    Sha256::new()
+        .chain_update([vote_for as u8])
+        .chain_update(nonce)
+        .finalize()
+        .into()
+}
+
+impl Procedure<Petition> {
+    /// like [`into_referendum`](Self::into_referendum), but for a referendum
+    /// open to commitments for `commit_time`, then to reveals for
+    /// `reveal_time`
+    pub fn into_commit_reveal_referendum(
+        self,
+        commit_time: Duration,
+        reveal_time: Duration
+    ) -> Result<Procedure<CommitRevealReferendum>, Self> {
+        if self.is_approved() {
+            let commit_end_date = self.clock.now() + commit_time;
+
+            Ok(self.transition(CommitRevealReferendum {
+                commit_end_date,
+                reveal_end_date: commit_end_date + reveal_time,
+                commitments: HashMap::new(),
+                have_revealed: HashSet::new(),
+                votes_for: 0,
+                votes_against: 0
+            }))
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl Procedure<CommitRevealReferendum> {
+    /// the date after which commitments are no longer accepted, and reveals
+    /// are
+    pub fn commit_end_date(&self) -> DateTime {
+        self.stage.commit_end_date
+    }
+
+    /// the date after which reveals are no longer accepted
+    pub fn reveal_end_date(&self) -> DateTime {
+        self.stage.reveal_end_date
+    }
+
+    pub fn is_committing(&self) -> bool {
+        self.clock.now() < self.stage.commit_end_date
+    }
+
+    pub fn is_revealing(&self) -> bool {
+        let now = self.clock.now();
+
+        self.stage.commit_end_date <= now && now < self.stage.reveal_end_date
+    }
+
+    pub fn commitment_count(&self) -> u64 {
+        self.stage.commitments.len() as u64
+    }
+
+    pub fn reveal_count(&self) -> u64 {
+        self.stage.have_revealed.len() as u64
+    }
+
+    /// revealed votes for adoption
+    pub fn votes_for(&self) -> u64 {
+        self.stage.votes_for
+    }
+
+    /// revealed votes against adoption
+    pub fn votes_against(&self) -> u64 {
+        self.stage.votes_against
+    }
+
+    /// commits `person_id` to the vote hidden by `commitment`, see
+    /// [`commitment`]
+    ///
+    /// error and does nothing if the commit phase is over, if `person_id` is
+    /// not an eligible elector, or if they have already committed
+    pub fn commit(&mut self, person_id: PersonId, commitment: [u8; 32]) -> Result<(), VoteError> {
+        if !self.is_committing() {
+            return Err(VoteError::Closed);
+        }
+
+        if !self.motion.is_elector(person_id) {
+            return Err(VoteError::NotElector(person_id));
+        }
+
+        if !self.is_eligible(person_id) {
+            return Err(VoteError::Ineligible(person_id));
+        }
+
+        if self.stage.commitments.contains_key(&person_id) {
+            return Err(VoteError::AlreadyVoted(person_id));
+        }
+
+        self.stage.commitments.insert(person_id, commitment);
+
+        self.notify_vote();
+
+        Ok(())
+    }
+
+    /// reveals the vote to which `person_id` committed, counting it
+    ///
+    /// error and does nothing if the reveal phase is not under way
+    /// ([`VoteError::WrongStage`] before, [`VoteError::Closed`] after), if
+    /// `person_id` has not committed or has already revealed, or if the vote
+    /// does not match the commitment
+    pub fn reveal(
+        &mut self,
+        person_id: PersonId,
+        vote_for: bool,
+        nonce: &[u8]
+    ) -> Result<(), VoteError> {
+        if self.is_committing() {
+            return Err(VoteError::WrongStage);
+        }
+
+        if !self.is_revealing() {
+            return Err(VoteError::Closed);
+        }
+
+        let Some(expected) = self.stage.commitments.get(&person_id) else {
+            return Err(VoteError::NotVoted(person_id));
+        };
+
+        if self.stage.have_revealed.contains(&person_id) {
+            return Err(VoteError::AlreadyVoted(person_id));
+        }
+
+        if commitment(vote_for, nonce) != *expected {
+            return Err(VoteError::InvalidBallot(person_id));
+        }
+
+        if vote_for {
+            self.stage.votes_for += 1;
+        } else {
+            self.stage.votes_against += 1;
+        }
+
+        self.stage.have_revealed.insert(person_id);
+
+        self.notify_vote();
+
+        Ok(())
+    }
+
+    /// passes if there are more revealed votes for than against
+    ///
+    /// returns Err(self) unchanged until the reveal phase is over, or if the
+    /// motion is rejected
+    pub fn pass(self) -> Result<Procedure<Passed>, Self> {
+        let is_over = self.stage.reveal_end_date <= self.clock.now();
+
+        if is_over && self.stage.votes_for > self.stage.votes_against {
+            Ok(self.transition(Passed))
+        } else {
+            Err(self)
+        }
+    }
+}
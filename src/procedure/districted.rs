@@ -0,0 +1,161 @@
+//! referendums decided by a majority of districts
+
+use crate::PersonId;
+use super::{
+    Procedure,
+    ProcedureStage,
+    StageName,
+    Petition,
+    Passed,
+    VoteError,
+    DateTime,
+    sealed
+};
+
+use chrono::Duration;
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// identifies a district of the electorate
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DistrictId(pub u64);
+
+/// variant of [`Referendum`](super::Referendum) for federal bodies, in which
+/// each elector belongs to a district
+///
+/// each district is carried by a majority of its own votes, with an even split
+/// being a rejection, and the motion passes if carried by an absolute majority
+/// of districts, whatever the popular vote
+///
+/// voting closes at a set date
+#[derive(Clone, Debug)]
+pub struct DistrictedReferendum {
+    end_date: DateTime,
+    districts: HashMap<PersonId, DistrictId>,
+    have_voted: HashSet<PersonId>,
+    /// votes for and against in each district with at least one elector
+    tallies: BTreeMap<DistrictId, DistrictTally>
+}
+
+/// the votes of a single district
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DistrictTally {
+    pub votes_for: u64,
+    pub votes_against: u64
+}
+
+impl ProcedureStage for DistrictedReferendum {
+    const NAME: StageName = StageName::DistrictedReferendum;
+}
+impl sealed::Sealed for DistrictedReferendum {}
+
+impl DistrictTally {
+    pub fn is_carried(&self) -> bool {
+        self.votes_for > self.votes_against
+    }
+}
+
+impl Procedure<Petition> {
+    /// like [`into_referendum`](Self::into_referendum), but for a referendum
+    /// decided by district, `district` giving the district of each elector
+    pub fn into_districted_referendum(
+        self,
+        vote_time: Duration,
+        district: impl Fn(PersonId) -> DistrictId
+    ) -> Result<Procedure<DistrictedReferendum>, Self> {
+        if !self.is_approved() {
+            return Err(self);
+        }
+
+        let districts = self.motion.electors.iter()
+            .map(|&id| (id, district(id)))
+            .collect::<HashMap<_, _>>();
+
+        let tallies = districts.values()
+            .map(|&district| (district, DistrictTally::default()))
+            .collect();
+
+        let end_date = self.clock.now() + vote_time;
+
+        Ok(self.transition(DistrictedReferendum {
+            end_date,
+            districts,
+            have_voted: HashSet::new(),
+            tallies
+        }))
+    }
+}
+
+impl Procedure<DistrictedReferendum> {
+    /// the date after which votes are no longer accepted
+    pub fn end_date(&self) -> DateTime {
+        self.stage.end_date
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.clock.now() < self.stage.end_date
+    }
+
+    pub fn district_of(&self, person_id: PersonId) -> Option<DistrictId> {
+        self.stage.districts.get(&person_id).copied()
+    }
+
+    /// the votes of each district, in order of ID
+    pub fn district_tallies(&self) -> impl Iterator<Item = (DistrictId, DistrictTally)> + '_ {
+        self.stage.tallies.iter().map(|(&id, &tally)| (id, tally))
+    }
+
+    pub fn district_count(&self) -> u64 {
+        self.stage.tallies.len() as u64
+    }
+
+    /// districts in which the motion is currently carried
+    pub fn districts_carried(&self) -> u64 {
+        self.stage.tallies.values().filter(|tally| tally.is_carried()).count() as u64
+    }
+
+    /// error and does nothing if voting has closed, or if `person_id` is not an
+    /// eligible elector or has already voted
+    pub fn register_vote(&mut self, person_id: PersonId, vote_for: bool) -> Result<(), VoteError> {
+        if !self.is_open() {
+            return Err(VoteError::Closed);
+        }
+
+        let Some(district) = self.district_of(person_id) else {
+            return Err(VoteError::NotElector(person_id));
+        };
+
+        if !self.is_eligible(person_id) {
+            return Err(VoteError::Ineligible(person_id));
+        }
+
+        if !self.stage.have_voted.insert(person_id) {
+            return Err(VoteError::AlreadyVoted(person_id));
+        }
+
+        // every district of an elector has a tally.
+        let tally = self.stage.tallies.get_mut(&district).unwrap();
+
+        if vote_for {
+            tally.votes_for += 1;
+        } else {
+            tally.votes_against += 1;
+        }
+
+        self.notify_vote();
+
+        Ok(())
+    }
+
+    /// passes if the motion is carried in an absolute majority of districts
+    ///
+    /// returns Err(self) unchanged while voting is open, or if the motion is
+    /// rejected
+    pub fn pass(self) -> Result<Procedure<Passed>, Self> {
+        if !self.is_open() && self.districts_carried() > self.district_count() / 2 {
+            Ok(self.transition(Passed))
+        } else {
+            Err(self)
+        }
+    }
+}
@@ -0,0 +1,143 @@
+//! errors arising from the electoral procedure
+
+use crate::PersonId;
+use super::{Procedure, Referendum, StageName};
+
+use std::{error::Error, fmt};
+
+/// reason for which a vote was rejected, naming the offending voter
+///
+/// a rejected vote leaves the procedure unchanged
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VoteError {
+    /// the voter is not a developer of the motion
+    NotDeveloper(PersonId),
+    /// the person is already a developer of the motion
+    AlreadyDeveloper(PersonId),
+    /// the voter is not part of the petitioner group
+    NotPetitioner(PersonId),
+    /// the voter is not part of the motion's electorate
+    NotElector(PersonId),
+    /// the voter does not meet the additional eligibility condition of the
+    /// procedure
+    Ineligible(PersonId),
+    /// the voter has already voted in this stage
+    AlreadyVoted(PersonId),
+    /// the voter has not yet voted in this stage
+    NotVoted(PersonId),
+    /// the voter was issued a ballot token, and may only vote with it
+    TokenIssued(PersonId),
+    /// the vote was given no weight
+    ZeroWeight(PersonId),
+    /// the delegation by the voter would form a cycle
+    DelegationCycle(PersonId),
+    /// the ballot of the voter is malformed
+    InvalidBallot(PersonId),
+    /// the weight of the vote would overflow the tally
+    Overflow(PersonId),
+    /// the voter cannot afford the votes with their remaining credits
+    InsufficientCredits(PersonId),
+    /// the person may not veto the motion
+    NotVetoer(PersonId),
+    /// the ballot token is invalid or has already been spent
+    InvalidToken,
+    /// voting has closed
+    Closed,
+    /// the procedure is not in a stage accepting this kind of vote
+    WrongStage
+}
+
+impl fmt::Display for VoteError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::NotDeveloper(_) => "voter is not a developer of the motion",
+            Self::AlreadyDeveloper(_) => "person is already a developer of the motion",
+            Self::NotPetitioner(_) => "voter is not part of the petitioner group",
+            Self::NotElector(_) => "voter is not part of the electorate",
+            Self::Ineligible(_) => "voter does not meet the eligibility condition",
+            Self::AlreadyVoted(_) => "voter has already voted in this stage",
+            Self::NotVoted(_) => "voter has not yet voted in this stage",
+            Self::TokenIssued(_) => "voter was issued a ballot token and must vote with it",
+            Self::ZeroWeight(_) => "vote must have a nonzero weight",
+            Self::DelegationCycle(_) => "delegation would form a cycle",
+            Self::InvalidBallot(_) => "ballot is malformed",
+            Self::Overflow(_) => "vote weight would overflow the tally",
+            Self::InsufficientCredits(_) => "voter has too few credits left for these votes",
+            Self::NotVetoer(_) => "person may not veto the motion",
+            Self::InvalidToken => "ballot token is invalid or already spent",
+            Self::Closed => "voting has closed",
+            Self::WrongStage => "procedure is not in a stage accepting this vote"
+        })
+    }
+}
+
+impl Error for VoteError {}
+
+/// reason for which a referendum failed to pass, handing back the procedure
+/// unchanged
+#[derive(Debug)]
+pub enum PassError {
+    /// too few electors participated for the result to be valid
+    QuorumNotMet(Procedure<Referendum>),
+    /// the motion did not obtain a majority, or was vetoed
+    Rejected(Procedure<Referendum>)
+}
+
+impl PassError {
+    pub fn into_procedure(self) -> Procedure<Referendum> {
+        match self {
+            Self::QuorumNotMet(p) | Self::Rejected(p) => p
+        }
+    }
+}
+
+impl fmt::Display for PassError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::QuorumNotMet(_) => "quorum not met",
+            Self::Rejected(_) => "motion rejected by majority or veto"
+        })
+    }
+}
+
+impl Error for PassError {}
+
+/// reason for which a sequence of stages cannot form a
+/// [`Pipeline`](super::Pipeline), or a motion cannot follow it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PipelineError {
+    /// the sequence does not begin with the prototype
+    NoPrototype,
+    /// the stage is listed more than once
+    Repeated(StageName),
+    /// the crate provides no transition between the two stages
+    Unsupported {
+        from: StageName,
+        to: StageName
+    },
+    /// the pipeline skips the petition, which the motion may not, see
+    /// [`into_referendum_fast_track`](super::Procedure::into_referendum_fast_track)
+    FastTrackUnavailable,
+    /// the petitioner ratio is not within (0, 1]
+    InvalidRatio,
+    /// the pipeline holds a districted referendum, but the elector is not
+    /// assigned a district
+    NoDistrict(PersonId)
+}
+
+impl fmt::Display for PipelineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NoPrototype => f.write_str("pipeline does not begin with the prototype"),
+            Self::Repeated(stage) => write!(f, "{stage} stage is listed more than once"),
+            Self::Unsupported { from, to } => {
+                write!(f, "no transition from {from} stage to {to} stage")
+            }
+            Self::FastTrackUnavailable => f.write_str("motion may not skip the petition"),
+            Self::InvalidRatio => f.write_str("petitioner ratio is not within (0, 1]"),
+            Self::NoDistrict(id) => write!(f, "elector {id} is not assigned a district")
+        }
+    }
+}
+
+impl Error for PipelineError {}
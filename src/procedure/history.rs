@@ -0,0 +1,105 @@
+//! record of the transitions undergone by an electoral procedure
+
+use super::{Procedure, Passed, Retired, DateTime};
+
+use chrono::Duration;
+
+use std::fmt;
+
+/// the stage of an electoral procedure, as known at runtime
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StageName {
+    Prototype,
+    Proposal,
+    Petition,
+    Referendum,
+    RankedReferendum,
+    CommitRevealReferendum,
+    SecretReferendum,
+    QuadraticReferendum,
+    DistrictedReferendum,
+    Passed,
+    Retired
+}
+
+/// a transition between two stages of a procedure
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TransitionRecord {
+    /// `None` for the beginning of the procedure
+    pub from: Option<StageName>,
+    pub to: StageName,
+    pub at: DateTime
+}
+
+/// when each stage of a concluded procedure began, from which the time spent
+/// in each is derived
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Timeline {
+    /// oldest first. the last is the concluding stage, which has no duration
+    starts: Vec<(StageName, DateTime)>
+}
+
+impl Timeline {
+    fn new(history: &[TransitionRecord]) -> Self {
+        Self { starts: history.iter().map(|record| (record.to, record.at)).collect() }
+    }
+
+    /// each stage with the time it began, oldest first, including the
+    /// concluding stage
+    pub fn starts(&self) -> &[(StageName, DateTime)] {
+        &self.starts
+    }
+
+    /// time from the beginning of the procedure to its conclusion
+    pub fn total_duration(&self) -> Duration {
+        match (self.starts.first(), self.starts.last()) {
+            (Some((_, first)), Some((_, last))) => *last - *first,
+            _ => Duration::zero()
+        }
+    }
+
+    /// time spent in each stage before the conclusion, in order. a stage
+    /// entered more than once, such as the prototype, appears once per visit
+    pub fn stage_durations(&self) -> Vec<(StageName, Duration)> {
+        self.starts.windows(2)
+            .map(|pair| (pair[0].0, pair[1].1 - pair[0].1))
+            .collect()
+    }
+}
+
+impl Procedure<Passed> {
+    pub fn timeline(&self) -> Timeline {
+        Timeline::new(&self.history)
+    }
+}
+
+impl Procedure<Retired> {
+    pub fn timeline(&self) -> Timeline {
+        Timeline::new(&self.history)
+    }
+}
+
+impl StageName {
+    /// the name of the stage in lowercase words, as displayed
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Prototype => "prototype",
+            Self::Proposal => "proposal",
+            Self::Petition => "petition",
+            Self::Referendum => "referendum",
+            Self::RankedReferendum => "ranked referendum",
+            Self::CommitRevealReferendum => "commit-reveal referendum",
+            Self::SecretReferendum => "secret referendum",
+            Self::QuadraticReferendum => "quadratic referendum",
+            Self::DistrictedReferendum => "districted referendum",
+            Self::Passed => "passed",
+            Self::Retired => "retired"
+        }
+    }
+}
+
+impl fmt::Display for StageName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
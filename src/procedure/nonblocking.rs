@@ -0,0 +1,153 @@
+//! votes whose eligibility is checked against an asynchronous store, and
+//! waiting on dates without blocking
+
+use crate::{PersonId, AsyncStore, Clock};
+use super::{
+    Procedure,
+    Proposal,
+    Petition,
+    Referendum,
+    VoteError,
+    DateTime,
+    CLOCK_POLL_INTERVAL
+};
+
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+    thread
+};
+
+impl Procedure<Proposal> {
+    /// completes once the debate is over, immediately if it already is, see
+    /// [`wait_and_into_petition`](Self::wait_and_into_petition)
+    ///
+    /// no runtime is required: the wait is timed on a separate thread, which
+    /// checks the clock at least every [`CLOCK_POLL_INTERVAL`] and wakes the
+    /// task once the end date is reached
+    pub async fn await_end_date(&self) {
+        Timer::new(self.clock.clone(), self.stage.end_date).await
+    }
+}
+
+impl Procedure<Petition> {
+    /// like [`register_approval_vote`](Self::register_approval_vote), but the
+    /// voter must also be eligible according to `store`, checked without
+    /// blocking
+    pub async fn register_approval_vote_async(
+        &mut self,
+        person_id: PersonId,
+        store: &impl AsyncStore
+    ) -> Result<(), VoteError> {
+        if !store.is_eligible(person_id).await {
+            return Err(VoteError::Ineligible(person_id));
+        }
+
+        self.register_approval_vote(person_id)
+    }
+}
+
+impl Procedure<Referendum> {
+    /// like [`register_vote_for`](Self::register_vote_for), but the voter must
+    /// also be eligible according to `store`, checked without blocking
+    pub async fn register_vote_for_async(
+        &mut self,
+        person_id: PersonId,
+        store: &impl AsyncStore
+    ) -> Result<(), VoteError> {
+        if !store.is_eligible(person_id).await {
+            return Err(VoteError::Ineligible(person_id));
+        }
+
+        self.register_vote_for(person_id)
+    }
+
+    /// see [`register_vote_for_async`](Self::register_vote_for_async)
+    pub async fn register_vote_against_async(
+        &mut self,
+        person_id: PersonId,
+        store: &impl AsyncStore
+    ) -> Result<(), VoteError> {
+        if !store.is_eligible(person_id).await {
+            return Err(VoteError::Ineligible(person_id));
+        }
+
+        self.register_vote_against(person_id)
+    }
+}
+
+/// future completing once `clock` reaches `date`
+///
+/// the sleeping thread checks the clock at least every [`CLOCK_POLL_INTERVAL`],
+/// so that a [`MockClock`](crate::MockClock) completes the timer soon after it
+/// is advanced past the date, and only then
+struct Timer {
+    clock: Arc<dyn Clock + Send + Sync>,
+    date: DateTime,
+    state: Arc<Mutex<TimerState>>
+}
+
+#[derive(Default)]
+struct TimerState {
+    /// the waker of the latest poll, to be woken by the sleeping thread
+    waker: Option<Waker>,
+    /// whether a thread is sleeping on behalf of the timer
+    is_sleeping: bool
+}
+
+impl Timer {
+    fn new(clock: Arc<dyn Clock + Send + Sync>, date: DateTime) -> Self {
+        Self { clock, date, state: Arc::default() }
+    }
+}
+
+impl Future for Timer {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+        if self.clock.now() >= self.date {
+            return Poll::Ready(());
+        }
+
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+
+        state.waker = Some(cx.waker().clone());
+
+        if !state.is_sleeping {
+            state.is_sleeping = true;
+
+            let shared = Arc::clone(&self.state);
+            let clock = Arc::clone(&self.clock);
+            let date = self.date;
+
+            thread::spawn(move || {
+                // negative durations cannot be converted, the date then being
+                // past.
+                while let Ok(remaining) = (date - clock.now()).to_std() {
+                    if remaining.is_zero() {
+                        break;
+                    }
+
+                    thread::sleep(remaining.min(CLOCK_POLL_INTERVAL));
+                }
+
+                // the thread is done once the lock is released, so that a poll
+                // following the wake starts another if needed.
+                let waker = {
+                    let mut state = shared.lock().unwrap_or_else(|e| e.into_inner());
+
+                    state.is_sleeping = false;
+                    state.waker.take()
+                };
+
+                if let Some(waker) = waker {
+                    waker.wake();
+                }
+            });
+        }
+
+        Poll::Pending
+    }
+}
@@ -0,0 +1,17 @@
+//! callbacks notified of the progress of a procedure
+
+use super::{StageName, TransitionRecord};
+
+/// called with the current stage whenever a vote is registered, see
+/// [`Procedure::on_vote`](super::Procedure::on_vote)
+pub type VoteObserver = Box<dyn FnMut(StageName) + Send + Sync>;
+
+/// called with each transition as it happens, see
+/// [`Procedure::on_transition`](super::Procedure::on_transition)
+pub type TransitionObserver = Box<dyn FnMut(&TransitionRecord) + Send + Sync>;
+
+#[derive(Default)]
+pub(super) struct Observers {
+    pub(super) on_vote: Vec<VoteObserver>,
+    pub(super) on_transition: Vec<TransitionObserver>
+}
@@ -0,0 +1,91 @@
+//! resolution of a referendum
+
+use crate::PersonId;
+
+/// how a referendum resolved, with the final tallies including delegated votes
+///
+/// an even split is a rejection, see [`Procedure`](super::Procedure)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Passed {
+        for_votes: u64,
+        against_votes: u64
+    },
+    /// rejected by majority, or by veto whatever the majority
+    Rejected {
+        for_votes: u64,
+        against_votes: u64,
+        vetoed: bool
+    }
+}
+
+/// how a referendum resolved, given a minimum turnout for the result to be
+/// valid, see
+/// [`Procedure::finalize_with_min_turnout`](super::Procedure::finalize_with_min_turnout)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ReferendumResult {
+    Decided(Outcome),
+    /// turnout was too low for the result to be valid, whatever the majority
+    Inconclusive {
+        turnout: f32
+    }
+}
+
+/// how a referendum in which votes for and against are equal is resolved, see
+/// [`Procedure::pass_with_tiebreak`](super::Procedure::pass_with_tiebreak)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TieBreak {
+    /// the rule of [`Procedure::pass`](super::Procedure::pass)
+    #[default]
+    RejectOnTie,
+    PassOnTie,
+    /// the side on which the person voted wins, directly or through
+    /// delegation. the motion is rejected if they abstained, did not vote, or
+    /// voted by token
+    CastingVote(PersonId)
+}
+
+impl Outcome {
+    pub(super) fn new(for_votes: u64, against_votes: u64) -> Self {
+        if for_votes > against_votes {
+            Self::Passed { for_votes, against_votes }
+        } else {
+            Self::Rejected { for_votes, against_votes, vetoed: false }
+        }
+    }
+
+    /// the outcome of a motion which only takes effect if the motion of
+    /// `prerequisite` passed: rejected, with the same tallies, if it did not
+    pub fn conditional_on(self, prerequisite: &Outcome) -> Self {
+        if prerequisite.is_passed() {
+            self
+        } else {
+            Self::Rejected {
+                for_votes: self.for_votes(),
+                against_votes: self.against_votes(),
+                vetoed: self.is_vetoed()
+            }
+        }
+    }
+
+    pub fn is_passed(&self) -> bool {
+        matches!(self, Self::Passed { .. })
+    }
+
+    pub fn is_vetoed(&self) -> bool {
+        matches!(self, Self::Rejected { vetoed: true, .. })
+    }
+
+    pub fn for_votes(&self) -> u64 {
+        match *self {
+            Self::Passed { for_votes, .. } | Self::Rejected { for_votes, .. } => for_votes
+        }
+    }
+
+    pub fn against_votes(&self) -> u64 {
+        match *self {
+            Self::Passed { against_votes, .. }
+            | Self::Rejected { against_votes, .. } => against_votes
+        }
+    }
+}
@@ -0,0 +1,325 @@
+//! configurable sequences of stages, followed by runtime-dispatched procedures
+
+use crate::{Motion, MotionCategory, PersonId};
+use super::{
+    ProcedureAny,
+    StageName,
+    PipelineError,
+    DistrictId,
+    IssuedTokens,
+    MIN_PROPOSAL_DURATION,
+    PETITIONER_RATIO,
+    QUADRATIC_BUDGET
+};
+
+use chrono::Duration;
+
+use std::collections::HashMap;
+
+/// the ordered stages a procedure goes through, beginning with the prototype,
+/// along with the parameters of each transition, such that a
+/// [`ProcedureAny`] can be driven from configuration with
+/// [`advance`](ProcedureAny::advance)
+///
+/// only transitions provided by the crate can be chained, see
+/// [`Pipeline::new`]. the petition may be listed several times in a row, each
+/// round drawing new petitioners who must approve the motion again. reverting
+/// to the prototype, rerolling a petition and retiring the motion are not part
+/// of the sequence and remain available as usual
+///
+/// the referendum may be any of its variants, such as the
+/// [`SecretReferendum`](super::SecretReferendum), whose parameters are set
+/// with the `with_*` methods. a secret referendum hands out tokens as it
+/// opens, so it is only entered by
+/// [`advance_issuing`](ProcedureAny::advance_issuing)
+///
+/// skipping the petition is reserved for some motions, so a motion should be
+/// checked against the pipeline with [`validate`](Pipeline::validate) before
+/// its procedure begins
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pipeline {
+    stages: Vec<StageName>,
+    proposal_time: Duration,
+    petitioner_ratio: f32,
+    vote_time: Duration,
+    reveal_time: Duration,
+    options: Vec<String>,
+    budget: u64,
+    districts: HashMap<PersonId, DistrictId>
+}
+
+impl Pipeline {
+    /// error if `stages` does not begin with the prototype, lists a stage other
+    /// than the petition more than once, or chains two stages without a
+    /// transition between them. the supported transitions are:
+    ///
+    /// - prototype to proposal
+    /// - prototype to referendum, for the motions allowed by
+    ///   [`validate`](Self::validate)
+    /// - proposal to petition
+    /// - proposal to referendum, for the motions allowed by
+    ///   [`validate`](Self::validate)
+    /// - petition to petition, for a further round, see
+    ///   [`into_next_petition`](super::Procedure::into_next_petition)
+    /// - petition to any referendum
+    /// - any referendum to passed, except for the ranked referendum, which
+    ///   only determines a winning option
+    ///
+    /// the debate, the referendum and the reveal phase of a commit-reveal
+    /// referendum last [`MIN_PROPOSAL_DURATION`], every petition round is
+    /// sized by [`PETITIONER_RATIO`] and electors of a quadratic referendum
+    /// hold [`QUADRATIC_BUDGET`] credits unless set otherwise. a ranked
+    /// referendum has no options and no elector has a district until set
+    pub fn new(stages: Vec<StageName>) -> Result<Self, PipelineError> {
+        if stages.first() != Some(&StageName::Prototype) {
+            return Err(PipelineError::NoPrototype);
+        }
+
+        for (idx, &stage) in stages.iter().enumerate() {
+            if stage != StageName::Petition && stages[..idx].contains(&stage) {
+                return Err(PipelineError::Repeated(stage));
+            }
+        }
+
+        for pair in stages.windows(2) {
+            if !is_supported(pair[0], pair[1]) {
+                return Err(PipelineError::Unsupported { from: pair[0], to: pair[1] });
+            }
+        }
+
+        let default_time = Duration::from_std(MIN_PROPOSAL_DURATION)
+            .expect("one week fits in a chrono duration");
+
+        Ok(Self {
+            stages,
+            proposal_time: default_time,
+            petitioner_ratio: PETITIONER_RATIO,
+            vote_time: default_time,
+            reveal_time: default_time,
+            options: Vec::new(),
+            budget: QUADRATIC_BUDGET,
+            districts: HashMap::new()
+        })
+    }
+
+    /// the pipeline, with a public debate of `proposal_time`
+    pub fn with_proposal_time(self, proposal_time: Duration) -> Self {
+        Self { proposal_time, ..self }
+    }
+
+    /// the pipeline, drawing petitioners by `ratio`, see
+    /// [`into_petition`](super::Procedure::into_petition). a ratio not within
+    /// (0, 1] is reported by [`validate`](Self::validate)
+    pub fn with_petitioner_ratio(self, ratio: f32) -> Self {
+        Self { petitioner_ratio: ratio, ..self }
+    }
+
+    /// the pipeline, with a referendum open to votes for `vote_time`. this is
+    /// the commit phase of a commit-reveal referendum
+    pub fn with_vote_time(self, vote_time: Duration) -> Self {
+        Self { vote_time, ..self }
+    }
+
+    /// the pipeline, with the votes of a commit-reveal referendum revealed
+    /// over `reveal_time`
+    pub fn with_reveal_time(self, reveal_time: Duration) -> Self {
+        Self { reveal_time, ..self }
+    }
+
+    /// the pipeline, with a ranked referendum over `options`
+    pub fn with_options(self, options: Vec<String>) -> Self {
+        Self { options, ..self }
+    }
+
+    /// the pipeline, with each elector of a quadratic referendum holding
+    /// `budget` credits
+    pub fn with_budget(self, budget: u64) -> Self {
+        Self { budget, ..self }
+    }
+
+    /// the pipeline, with the district of each elector of a districted
+    /// referendum. electors left out are reported by
+    /// [`validate`](Self::validate)
+    pub fn with_districts(
+        self,
+        districts: impl IntoIterator<Item = (PersonId, DistrictId)>
+    ) -> Self {
+        Self { districts: districts.into_iter().collect(), ..self }
+    }
+
+    pub fn stages(&self) -> &[StageName] {
+        &self.stages
+    }
+
+    /// error if `motion` could not go through every stage of the pipeline
+    ///
+    /// going from the prototype or the proposal to the referendum skips the
+    /// petition, which is only allowed for [`MotionCategory::Emergency`]
+    /// motions with developers, see
+    /// [`into_referendum_fast_track`](super::Procedure::into_referendum_fast_track).
+    /// the petitioner ratio must also be valid if there is a petition, and
+    /// every elector must have a district if there is a districted referendum
+    pub fn validate(&self, motion: &Motion) -> Result<(), PipelineError> {
+        use StageName::*;
+
+        let has_petition = self.stages.contains(&Petition);
+        let skips_petition = self.next(Prototype) == Some(Referendum)
+            || self.next(Proposal) == Some(Referendum);
+
+        if has_petition && !(self.petitioner_ratio > 0.0 && self.petitioner_ratio <= 1.0) {
+            return Err(PipelineError::InvalidRatio);
+        }
+
+        let may_skip = motion.category == MotionCategory::Emergency && !motion.is_anonymous();
+
+        if skips_petition && !may_skip {
+            return Err(PipelineError::FastTrackUnavailable);
+        }
+
+        if self.stages.contains(&DistrictedReferendum) {
+            let no_district = motion.electors.iter().find(|id| !self.districts.contains_key(id));
+
+            if let Some(&id) = no_district {
+                return Err(PipelineError::NoDistrict(id));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// the stage following `stage`, or `None` if it is the last or is not
+    /// part of the pipeline. for the petition, this follows the first round
+    pub fn next(&self, stage: StageName) -> Option<StageName> {
+        self.next_after(stage, 0)
+    }
+
+    /// the stage following the `n`th listing of `stage`, counting from 0
+    fn next_after(&self, stage: StageName, n: usize) -> Option<StageName> {
+        let idx = self.stages.iter()
+            .enumerate()
+            .filter(|&(_, &s)| s == stage)
+            .nth(n)?
+            .0;
+
+        self.stages.get(idx + 1).copied()
+    }
+}
+
+impl Default for Pipeline {
+    /// prototype, proposal, petition, referendum, passed
+    fn default() -> Self {
+        Self::new(vec![
+            StageName::Prototype,
+            StageName::Proposal,
+            StageName::Petition,
+            StageName::Referendum,
+            StageName::Passed
+        ]).expect("the standard pipeline is supported")
+    }
+}
+
+fn is_supported(from: StageName, to: StageName) -> bool {
+    use StageName::*;
+
+    matches!(
+        (from, to),
+        (Prototype, Proposal)
+            | (Prototype, Referendum)
+            | (Proposal, Petition)
+            | (Proposal, Referendum)
+            | (Petition, Petition)
+            | (
+                Petition,
+                Referendum
+                    | RankedReferendum
+                    | CommitRevealReferendum
+                    | SecretReferendum
+                    | QuadraticReferendum
+                    | DistrictedReferendum
+            )
+            | (
+                Referendum
+                    | CommitRevealReferendum
+                    | SecretReferendum
+                    | QuadraticReferendum
+                    | DistrictedReferendum,
+                Passed
+            )
+    )
+}
+
+impl ProcedureAny {
+    /// moves to the stage following the current one in `pipeline`
+    ///
+    /// a petition follows the listing matching its
+    /// [`round`](super::Procedure::round), and each further round draws new
+    /// petitioners
+    ///
+    /// returns Err(self) unchanged if the current stage is not part of
+    /// `pipeline` or is its last, if the next stage is a secret referendum,
+    /// whose tokens would be lost, see [`advance_issuing`](Self::advance_issuing),
+    /// if the motion is not valid for `pipeline`, see [`Pipeline::validate`],
+    /// or if the transition itself fails
+    pub fn advance(self, pipeline: &Pipeline) -> Result<Self, Self> {
+        if self.next_in(pipeline) == Some(StageName::SecretReferendum) {
+            return Err(self);
+        }
+
+        self.advance_issuing(pipeline).map(|(procedure, _)| procedure)
+    }
+
+    /// like [`advance`](Self::advance), also returning the tokens issued on
+    /// entering a secret referendum, see
+    /// [`into_secret_referendum`](super::Procedure::into_secret_referendum).
+    /// no tokens are issued by other transitions
+    pub fn advance_issuing(self, pipeline: &Pipeline) -> Result<(Self, IssuedTokens), Self> {
+        use StageName::*;
+
+        if pipeline.validate(self.motion()).is_err() {
+            return Err(self);
+        }
+
+        let Some(next) = self.next_in(pipeline) else {
+            return Err(self);
+        };
+
+        let vote_time = pipeline.vote_time;
+
+        let advanced = match next {
+            SecretReferendum => return self.into_secret_referendum(vote_time),
+            Proposal => self.into_proposal(pipeline.proposal_time),
+            Petition if self.stage() == Petition => {
+                self.into_next_petition(pipeline.petitioner_ratio)
+            }
+            Petition => self.into_petition(pipeline.petitioner_ratio),
+            Referendum if matches!(self.stage(), Prototype | Proposal) => {
+                self.into_referendum_fast_track(vote_time)
+            }
+            Referendum => self.into_referendum(vote_time),
+            RankedReferendum => self.into_ranked_referendum(pipeline.options.clone()),
+            CommitRevealReferendum => {
+                self.into_commit_reveal_referendum(vote_time, pipeline.reveal_time)
+            }
+            QuadraticReferendum => self.into_quadratic_referendum(vote_time, pipeline.budget),
+            // `validate` ensures that every elector has a district.
+            DistrictedReferendum => {
+                self.into_districted_referendum(vote_time, |id| pipeline.districts[&id])
+            }
+            Passed => self.pass(),
+            // `Pipeline::new` rejects any other transition.
+            Prototype | Retired => Err(self)
+        };
+
+        advanced.map(|procedure| (procedure, IssuedTokens::new()))
+    }
+
+    /// the stage following the current one in `pipeline`, if any
+    fn next_in(&self, pipeline: &Pipeline) -> Option<StageName> {
+        let round = match self {
+            Self::Petition(p) => p.round() as usize,
+            _ => 1
+        };
+
+        pipeline.next_after(self.stage(), round - 1)
+    }
+}
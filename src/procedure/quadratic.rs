@@ -0,0 +1,172 @@
+//! referendums in which electors buy votes with credits at a quadratic cost
+
+use crate::{PersonId, Side};
+use super::{
+    Procedure,
+    ProcedureStage,
+    StageName,
+    Petition,
+    Passed,
+    VoteError,
+    DateTime,
+    sealed
+};
+
+use chrono::Duration;
+
+use std::collections::HashMap;
+
+/// variant of [`Referendum`](super::Referendum) in which electors express the
+/// intensity of their preference
+///
+/// each elector holds the same budget of credits, and casting `n` votes for a
+/// side costs `n²` credits. an elector may add votes over several ballots, all
+/// for the same side, paying for their total: going from `n` to `m` votes
+/// costs `m² - n²` credits
+#[derive(Clone, Debug)]
+pub struct QuadraticReferendum {
+    end_date: DateTime,
+    budget: u64,
+    /// the side chosen by each voter and the votes they have cast for it
+    have_voted: HashMap<PersonId, (Side, u64)>,
+    votes_for: u64,
+    votes_against: u64
+}
+
+/// the credits held by each elector of a quadratic referendum in a
+/// [`Pipeline`](super::Pipeline) unless set otherwise, enough for 10 votes
+pub const QUADRATIC_BUDGET: u64 = 100;
+
+impl ProcedureStage for QuadraticReferendum {
+    const NAME: StageName = StageName::QuadraticReferendum;
+}
+impl sealed::Sealed for QuadraticReferendum {}
+
+impl Procedure<Petition> {
+    /// like [`into_referendum`](Self::into_referendum), but for a quadratic
+    /// referendum in which each elector holds `budget` credits
+    pub fn into_quadratic_referendum(
+        self,
+        vote_time: Duration,
+        budget: u64
+    ) -> Result<Procedure<QuadraticReferendum>, Self> {
+        if self.is_approved() {
+            let end_date = self.clock.now() + vote_time;
+
+            Ok(self.transition(QuadraticReferendum {
+                end_date,
+                budget,
+                have_voted: HashMap::new(),
+                votes_for: 0,
+                votes_against: 0
+            }))
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl Procedure<QuadraticReferendum> {
+    /// the date after which votes are no longer accepted
+    pub fn end_date(&self) -> DateTime {
+        self.stage.end_date
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.clock.now() < self.stage.end_date
+    }
+
+    /// the credits held by each elector before voting
+    pub fn budget(&self) -> u64 {
+        self.stage.budget
+    }
+
+    /// the credits `person_id` has left to spend
+    pub fn remaining_budget(&self, person_id: PersonId) -> u64 {
+        let votes = self.stage.have_voted.get(&person_id).map_or(0, |&(_, votes)| votes);
+
+        // votes are only accepted while their cost is within the budget.
+        self.stage.budget - votes * votes
+    }
+
+    /// electors who have cast at least one vote
+    pub fn voter_count(&self) -> u64 {
+        self.stage.have_voted.len() as u64
+    }
+
+    pub fn votes_for(&self) -> u64 {
+        self.stage.votes_for
+    }
+
+    pub fn votes_against(&self) -> u64 {
+        self.stage.votes_against
+    }
+
+    /// casts `votes` more votes for `side` on behalf of `person_id`, deducting
+    /// their cost from the elector's budget
+    ///
+    /// error and does nothing if the referendum is closed, if `person_id` is
+    /// not an eligible elector, if `votes` is 0, if they have already voted for
+    /// the other side, or if they cannot afford the votes
+    pub fn register_quadratic(
+        &mut self,
+        person_id: PersonId,
+        side: Side,
+        votes: u64
+    ) -> Result<(), VoteError> {
+        if !self.is_open() {
+            return Err(VoteError::Closed);
+        }
+
+        if !self.motion.is_elector(person_id) {
+            return Err(VoteError::NotElector(person_id));
+        }
+
+        if !self.is_eligible(person_id) {
+            return Err(VoteError::Ineligible(person_id));
+        }
+
+        if votes == 0 {
+            return Err(VoteError::ZeroWeight(person_id));
+        }
+
+        let previous = match self.stage.have_voted.get(&person_id) {
+            Some(&(chosen, _)) if chosen != side => {
+                return Err(VoteError::InvalidBallot(person_id));
+            }
+            Some(&(_, previous)) => previous,
+            None => 0
+        };
+
+        let total = previous.checked_add(votes)
+            .filter(|&total| {
+                total.checked_mul(total).is_some_and(|cost| cost <= self.stage.budget)
+            })
+            .ok_or(VoteError::InsufficientCredits(person_id))?;
+
+        let tally = match side {
+            Side::For => &mut self.stage.votes_for,
+            Side::Against => &mut self.stage.votes_against
+        };
+
+        *tally = tally.checked_add(votes).ok_or(VoteError::Overflow(person_id))?;
+
+        self.stage.have_voted.insert(person_id, (side, total));
+
+        self.notify_vote();
+
+        Ok(())
+    }
+
+    /// passes if there are more votes for than against
+    ///
+    /// returns Err(self) unchanged while the referendum is open, or if the
+    /// motion is rejected
+    pub fn pass(self) -> Result<Procedure<Passed>, Self> {
+        if !self.is_open() && self.stage.votes_for > self.stage.votes_against {
+            Ok(self.transition(Passed))
+        } else {
+            Err(self)
+        }
+    }
+}
@@ -0,0 +1,142 @@
+//! referendums over several mutually exclusive options
+
+use crate::PersonId;
+use super::{Procedure, ProcedureStage, StageName, Petition, VoteError, sealed};
+
+use std::collections::HashSet;
+
+/// generalisation of [`Referendum`](super::Referendum) to several mutually
+/// exclusive options, such as competing versions of a motion
+///
+/// each voter ranks any number of options in order of preference, and the
+/// winner is determined by instant-runoff: the option with the fewest first
+/// preferences is eliminated and its ballots transferred to their next
+/// preference, until one option is preferred by an absolute majority of the
+/// remaining ballots
+#[derive(Clone, Debug)]
+pub struct RankedReferendum {
+    options: Vec<String>,
+    have_voted: HashSet<PersonId>,
+    /// ballots are not associated with voters, preserving secrecy
+    ballots: Vec<Vec<usize>>
+}
+
+impl ProcedureStage for RankedReferendum {
+    const NAME: StageName = StageName::RankedReferendum;
+}
+impl sealed::Sealed for RankedReferendum {}
+
+impl Procedure<Petition> {
+    /// like [`into_referendum`](Self::into_referendum), but for a referendum
+    /// over `options`
+    pub fn into_ranked_referendum(
+        self,
+        options: Vec<String>
+    ) -> Result<Procedure<RankedReferendum>, Self> {
+        if self.is_approved() {
+            Ok(self.transition(RankedReferendum {
+                options,
+                have_voted: HashSet::new(),
+                ballots: Vec::new()
+            }))
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl Procedure<RankedReferendum> {
+    pub fn options(&self) -> &[String] {
+        &self.stage.options
+    }
+
+    pub fn ballot_count(&self) -> u64 {
+        self.stage.ballots.len() as u64
+    }
+
+    /// `ranking` lists indices into [`options`](Self::options), most preferred
+    /// first. options left out are never preferred
+    ///
+    /// error and does nothing if `person_id` is not an elector or has already
+    /// voted, or if `ranking` is empty or contains an invalid or repeated index
+    pub fn register_ranked_vote(
+        &mut self,
+        person_id: PersonId,
+        ranking: Vec<usize>
+    ) -> Result<(), VoteError> {
+        if !self.motion.is_elector(person_id) {
+            return Err(VoteError::NotElector(person_id));
+        }
+
+        if !self.is_eligible(person_id) {
+            return Err(VoteError::Ineligible(person_id));
+        }
+
+        if self.stage.have_voted.contains(&person_id) {
+            return Err(VoteError::AlreadyVoted(person_id));
+        }
+
+        let mut ranked = HashSet::new();
+
+        let is_valid = !ranking.is_empty() && ranking.iter().all(|&option| {
+            option < self.stage.options.len() && ranked.insert(option)
+        });
+
+        if !is_valid {
+            return Err(VoteError::InvalidBallot(person_id));
+        }
+
+        self.stage.ballots.push(ranking);
+        self.stage.have_voted.insert(person_id);
+
+        self.notify_vote();
+
+        Ok(())
+    }
+
+    /// index of the winning option, by instant-runoff
+    ///
+    /// all options tied for the fewest preferences are eliminated together. if
+    /// every remaining option is tied, or no ballots were cast, there is no
+    /// winner
+    pub fn winner(&self) -> Option<usize> {
+        let option_count = self.stage.options.len();
+        let mut eliminated = vec![false; option_count];
+
+        loop {
+            let mut counts = vec![0_u64; option_count];
+            let mut active_ballots = 0;
+
+            for ballot in &self.stage.ballots {
+                let preference = ballot.iter().find(|&&option| !eliminated[option]);
+
+                if let Some(&option) = preference {
+                    counts[option] += 1;
+                    active_ballots += 1;
+                }
+            }
+
+            if active_ballots == 0 {
+                return None;
+            }
+
+            if let Some(option) = (0..option_count).find(|&o| counts[o] * 2 > active_ballots) {
+                return Some(option);
+            }
+
+            let remaining = (0..option_count)
+                .filter(|&o| !eliminated[o])
+                .collect::<Vec<_>>();
+
+            let fewest = remaining.iter().map(|&o| counts[o]).min()?;
+
+            if remaining.iter().all(|&o| counts[o] == fewest) {
+                return None;
+            }
+
+            for option in remaining.into_iter().filter(|&o| counts[o] == fewest) {
+                eliminated[option] = true;
+            }
+        }
+    }
+}
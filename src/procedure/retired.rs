@@ -0,0 +1,141 @@
+//! withdrawal of motions before their conclusion
+
+use crate::PersonId;
+use super::{
+    Procedure,
+    ProcedureStage,
+    StageName,
+    Prototype,
+    Proposal,
+    Petition,
+    Referendum,
+    RankedReferendum,
+    CommitRevealReferendum,
+    SecretReferendum,
+    QuadraticReferendum,
+    DistrictedReferendum,
+    VoteError,
+    DateTime,
+    absolute_majority,
+    sealed
+};
+
+/// motion was withdrawn before being carried, concluding the procedure
+///
+/// in the prototype stage, developers retire the motion by majority vote. once
+/// proposed, the motion is public, and is retired unconditionally, for
+/// instance by the body overseeing the procedure
+#[derive(Clone, Debug)]
+pub struct Retired {
+    reason: String,
+    retired_at: DateTime
+}
+
+impl ProcedureStage for Retired {
+    const NAME: StageName = StageName::Retired;
+}
+impl sealed::Sealed for Retired {}
+
+impl<St: ProcedureStage> Procedure<St> {
+    fn retire(self, reason: String) -> Procedure<Retired> {
+        let retired_at = self.clock.now();
+
+        self.transition(Retired { reason, retired_at })
+    }
+}
+
+impl Procedure<Prototype> {
+    /// error and does nothing if `person_id` has already voted or is not
+    /// developper
+    pub fn register_retire_vote(&mut self, person_id: PersonId) -> Result<(), VoteError> {
+        if !self.motion.developers.contains(&person_id) {
+            return Err(VoteError::NotDeveloper(person_id));
+        }
+
+        if !self.stage.retire_votes.insert(person_id) {
+            return Err(VoteError::AlreadyVoted(person_id));
+        }
+
+        self.notify_vote();
+
+        Ok(())
+    }
+
+    pub fn retire_votes(&self) -> u64 {
+        self.stage.retire_votes.len() as u64
+    }
+
+    /// returns Err(self) unchanged if an absolute majority of developers has
+    /// not voted to retire
+    pub fn into_retired(self, reason: impl Into<String>) -> Result<Procedure<Retired>, Self> {
+        if self.retire_votes() >= absolute_majority(self.motion.dev_count()) {
+            Ok(self.retire(reason.into()))
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl Procedure<Proposal> {
+    pub fn into_retired(self, reason: impl Into<String>) -> Procedure<Retired> {
+        self.retire(reason.into())
+    }
+}
+
+impl Procedure<Petition> {
+    pub fn into_retired(self, reason: impl Into<String>) -> Procedure<Retired> {
+        self.retire(reason.into())
+    }
+}
+
+impl Procedure<Referendum> {
+    /// votes already cast are discarded
+    pub fn into_retired(self, reason: impl Into<String>) -> Procedure<Retired> {
+        self.retire(reason.into())
+    }
+}
+
+impl Procedure<RankedReferendum> {
+    /// votes already cast are discarded
+    pub fn into_retired(self, reason: impl Into<String>) -> Procedure<Retired> {
+        self.retire(reason.into())
+    }
+}
+
+impl Procedure<CommitRevealReferendum> {
+    /// votes already cast are discarded
+    pub fn into_retired(self, reason: impl Into<String>) -> Procedure<Retired> {
+        self.retire(reason.into())
+    }
+}
+
+impl Procedure<SecretReferendum> {
+    /// votes already cast are discarded
+    pub fn into_retired(self, reason: impl Into<String>) -> Procedure<Retired> {
+        self.retire(reason.into())
+    }
+}
+
+impl Procedure<QuadraticReferendum> {
+    /// votes already cast are discarded
+    pub fn into_retired(self, reason: impl Into<String>) -> Procedure<Retired> {
+        self.retire(reason.into())
+    }
+}
+
+impl Procedure<DistrictedReferendum> {
+    /// votes already cast are discarded
+    pub fn into_retired(self, reason: impl Into<String>) -> Procedure<Retired> {
+        self.retire(reason.into())
+    }
+}
+
+impl Procedure<Retired> {
+    pub fn reason(&self) -> &str {
+        &self.stage.reason
+    }
+
+    pub fn retired_at(&self) -> DateTime {
+        self.stage.retired_at
+    }
+}
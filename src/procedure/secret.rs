@@ -0,0 +1,153 @@
+//! referendums which keep no record of who voted
+
+use crate::PersonId;
+use super::{
+    Procedure,
+    ProcedureStage,
+    StageName,
+    Petition,
+    Passed,
+    BallotToken,
+    VoteError,
+    DateTime,
+    sealed
+};
+
+use chrono::Duration;
+
+use std::collections::HashSet;
+
+/// variant of [`Referendum`](super::Referendum) in which votes are only cast
+/// with [`BallotToken`]s, and the identity of voters is never stored
+///
+/// a random token is issued to each eligible elector when the referendum
+/// opens, and the list linking tokens to electors is handed to the caller
+/// without being kept, so the tokens left unspent cannot be linked back to
+/// electors. neither during nor after the referendum does the procedure hold
+/// who has voted. in exchange, votes cannot be changed or delegated, and
+/// tokens cannot be reissued
+#[derive(Clone, Debug)]
+pub struct SecretReferendum {
+    end_date: DateTime,
+    unspent_tokens: HashSet<BallotToken>,
+    votes_for: u64,
+    votes_against: u64,
+    abstentions: u64
+}
+
+impl ProcedureStage for SecretReferendum {
+    const NAME: StageName = StageName::SecretReferendum;
+}
+impl sealed::Sealed for SecretReferendum {}
+
+/// each eligible elector with their token, to be handed to them in private
+pub type IssuedTokens = Vec<(PersonId, BallotToken)>;
+
+impl Procedure<Petition> {
+    /// like [`into_referendum`](Self::into_referendum), but for a secret
+    /// referendum, returned with the token of each eligible elector. the
+    /// tokens are not retained by the procedure, so they must be distributed
+    /// from this list
+    pub fn into_secret_referendum(
+        self,
+        vote_time: Duration
+    ) -> Result<(Procedure<SecretReferendum>, IssuedTokens), Self> {
+        if !self.is_approved() {
+            return Err(self);
+        }
+
+        let end_date = self.clock.now() + vote_time;
+        let mut rng = rand::thread_rng();
+
+        let tokens: IssuedTokens = self.motion.electors.iter()
+            .filter(|&&id| self.is_eligible(id))
+            .map(|&id| (id, BallotToken::random(&mut rng)))
+            .collect();
+
+        let referendum = SecretReferendum {
+            end_date,
+            unspent_tokens: tokens.iter().map(|&(_, token)| token).collect(),
+            votes_for: 0,
+            votes_against: 0,
+            abstentions: 0
+        };
+
+        Ok((self.transition(referendum), tokens))
+    }
+}
+
+impl Procedure<SecretReferendum> {
+    /// the date after which votes are no longer accepted
+    pub fn end_date(&self) -> DateTime {
+        self.stage.end_date
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.clock.now() < self.stage.end_date
+    }
+
+    pub fn votes_for(&self) -> u64 {
+        self.stage.votes_for
+    }
+
+    pub fn votes_against(&self) -> u64 {
+        self.stage.votes_against
+    }
+
+    pub fn abstentions(&self) -> u64 {
+        self.stage.abstentions
+    }
+
+    /// vote for adoption if `vote_for` by the holder of `token`
+    ///
+    /// error and does nothing if the referendum is closed, or if `token` was
+    /// not issued by this referendum or has already been spent
+    pub fn register_vote(&mut self, token: BallotToken, vote_for: bool) -> Result<(), VoteError> {
+        self.spend(token)?;
+
+        if vote_for {
+            self.stage.votes_for += 1;
+        } else {
+            self.stage.votes_against += 1;
+        }
+
+        self.notify_vote();
+
+        Ok(())
+    }
+
+    /// see [`register_vote`](Self::register_vote)
+    pub fn register_abstention(&mut self, token: BallotToken) -> Result<(), VoteError> {
+        self.spend(token)?;
+
+        self.stage.abstentions += 1;
+
+        self.notify_vote();
+
+        Ok(())
+    }
+
+    fn spend(&mut self, token: BallotToken) -> Result<(), VoteError> {
+        if !self.is_open() {
+            return Err(VoteError::Closed);
+        }
+
+        if !self.stage.unspent_tokens.remove(&token) {
+            return Err(VoteError::InvalidToken);
+        }
+
+        Ok(())
+    }
+
+    /// passes if there are more votes for than against
+    ///
+    /// returns Err(self) unchanged while the referendum is open, or if the
+    /// motion is rejected
+    pub fn pass(self) -> Result<Procedure<Passed>, Self> {
+        if !self.is_open() && self.stage.votes_for > self.stage.votes_against {
+            Ok(self.transition(Passed))
+        } else {
+            Err(self)
+        }
+    }
+}
@@ -0,0 +1,154 @@
+//! participation in each stage of a procedure, summarised
+
+use super::{
+    Procedure,
+    Prototype,
+    Proposal,
+    Petition,
+    Referendum,
+    RankedReferendum,
+    CommitRevealReferendum,
+    SecretReferendum,
+    QuadraticReferendum,
+    DistrictedReferendum
+};
+
+/// participation in the vote of the current stage of a procedure
+///
+/// the vote is that for proposal in the prototype stage, for reverting to
+/// prototype in the proposal stage, for approval in the petition stage, and
+/// that of the referendum. counts which do not apply to the stage are 0
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// people who may vote in the stage
+    pub eligible: u64,
+    /// people whose vote was cast, including by delegation
+    pub voters: u64,
+    /// eligible people who have yet to vote
+    pub remaining: u64,
+    /// weighted votes for, including delegated votes
+    pub votes_for: u64,
+    /// weighted votes against, including delegated votes
+    pub votes_against: u64,
+    pub abstentions: u64,
+    pub spoiled: u64
+}
+
+impl Stats {
+    fn new(eligible: u64, voters: u64) -> Self {
+        Self {
+            eligible,
+            voters,
+            remaining: eligible.saturating_sub(voters),
+            ..Self::default()
+        }
+    }
+}
+
+impl Procedure<Prototype> {
+    /// participation in the active proposal vote
+    pub fn stats(&self) -> Stats {
+        let voters = self.active_attempt().map_or(0, |a| a.have_voted.len() as u64);
+
+        Stats {
+            votes_for: self.proposal_votes(),
+            ..Stats::new(self.motion.dev_count() as u64, voters)
+        }
+    }
+}
+
+impl Procedure<Proposal> {
+    /// participation in the vote to revert to prototype
+    pub fn stats(&self) -> Stats {
+        Stats {
+            votes_for: self.stage.revert_votes,
+            ..Stats::new(self.motion.dev_count() as u64, self.stage.have_voted.len() as u64)
+        }
+    }
+}
+
+impl Procedure<Petition> {
+    pub fn stats(&self) -> Stats {
+        let eligible = self.stage.voter_ids.len() as u64;
+
+        Stats {
+            votes_for: self.stage.approval_votes,
+            ..Stats::new(eligible, self.stage.have_voted.len() as u64)
+        }
+    }
+}
+
+impl Procedure<Referendum> {
+    pub fn stats(&self) -> Stats {
+        let tally = self.tally();
+
+        Stats {
+            votes_for: tally.votes_for,
+            votes_against: tally.votes_against,
+            abstentions: self.stage.abstentions,
+            spoiled: self.stage.spoiled,
+            ..Stats::new(self.motion.elector_count() as u64, tally.voters)
+        }
+    }
+}
+
+impl Procedure<RankedReferendum> {
+    /// ballots rank options rather than vote for or against, so only
+    /// participation is counted
+    pub fn stats(&self) -> Stats {
+        Stats::new(self.motion.elector_count() as u64, self.ballot_count())
+    }
+}
+
+impl Procedure<CommitRevealReferendum> {
+    /// electors who have committed count as voters, but only revealed votes
+    /// are counted for and against
+    pub fn stats(&self) -> Stats {
+        Stats {
+            votes_for: self.votes_for(),
+            votes_against: self.votes_against(),
+            ..Stats::new(self.motion.elector_count() as u64, self.commitment_count())
+        }
+    }
+}
+
+impl Procedure<SecretReferendum> {
+    /// voters are counted from the ballots cast, since who has voted is not
+    /// recorded
+    pub fn stats(&self) -> Stats {
+        let voters = self.votes_for() + self.votes_against() + self.abstentions();
+
+        Stats {
+            votes_for: self.votes_for(),
+            votes_against: self.votes_against(),
+            abstentions: self.abstentions(),
+            ..Stats::new(self.motion.elector_count() as u64, voters)
+        }
+    }
+}
+
+impl Procedure<QuadraticReferendum> {
+    /// votes for and against are the votes bought, not the voters on each side
+    pub fn stats(&self) -> Stats {
+        Stats {
+            votes_for: self.votes_for(),
+            votes_against: self.votes_against(),
+            ..Stats::new(self.motion.elector_count() as u64, self.voter_count())
+        }
+    }
+}
+
+impl Procedure<DistrictedReferendum> {
+    /// votes for and against are summed over all districts, whatever the
+    /// districts carried
+    pub fn stats(&self) -> Stats {
+        let (votes_for, votes_against) = self.district_tallies()
+            .fold((0, 0), |(f, a), (_, tally)| (f + tally.votes_for, a + tally.votes_against));
+
+        Stats {
+            votes_for,
+            votes_against,
+            ..Stats::new(self.motion.elector_count() as u64, votes_for + votes_against)
+        }
+    }
+}
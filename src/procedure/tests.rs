@@ -0,0 +1,612 @@
+use super::*;
+use crate::{MockClock, Person, PersonList, Side, VoteError};
+
+use chrono::Utc;
+
+use rand::{SeedableRng, rngs::StdRng};
+
+/// a proposal of an anonymous motion over `size` electors, debated for a day
+/// by `clock`
+fn proposal(size: u64, clock: &MockClock) -> (Procedure<Proposal>, Vec<PersonId>) {
+    let electors = population(size);
+    let motion = Motion::new("motion", "", Vec::new(), electors.clone()).unwrap();
+
+    let proposal = Procedure::begin_with_clock(motion, clock.clone())
+        .into_proposal_unchecked(Duration::days(1))
+        .unwrap();
+
+    (proposal, electors)
+}
+
+/// a petition of the whole electorate of `size`, approved by all, with the
+/// clock driving it
+fn petition(size: u64) -> (Procedure<Petition>, Vec<PersonId>, MockClock) {
+    let clock = MockClock::new(Utc::now());
+    let (proposal, electors) = proposal(size, &clock);
+
+    clock.advance(Duration::days(1));
+
+    let mut petition = proposal
+        .into_petition_with_rng(1.0, &mut StdRng::seed_from_u64(0))
+        .unwrap();
+
+    for &id in &electors {
+        petition.register_approval_vote(id).unwrap();
+    }
+
+    (petition, electors, clock)
+}
+
+/// an open referendum on an anonymous motion over `size` electors, with the
+/// clock driving it
+fn referendum(size: u64) -> (Procedure<Referendum>, Vec<PersonId>, MockClock) {
+    let (petition, electors, clock) = petition(size);
+    let referendum = petition.into_referendum(Duration::days(1)).unwrap();
+
+    (referendum, electors, clock)
+}
+
+fn population(size: u64) -> Vec<PersonId> {
+    let persons: PersonList = (0..size)
+        .map(|i| Person { name: i.to_string(), external_id: None })
+        .collect();
+
+    persons.ids().collect()
+}
+
+#[test]
+fn delegation_chain_follows_last_voter() {
+    let (mut referendum, ids, _) = referendum(4);
+
+    referendum.delegate(ids[0], ids[1]).unwrap();
+    referendum.delegate(ids[1], ids[2]).unwrap();
+    referendum.register_vote_for(ids[2]).unwrap();
+
+    assert_eq!(referendum.votes_for(), 3);
+    assert_eq!(referendum.votes_against(), 0);
+    assert_eq!(referendum.turnout(), 0.75);
+}
+
+#[test]
+fn delegation_chain_without_voter_is_not_counted() {
+    let (mut referendum, ids, _) = referendum(3);
+
+    referendum.delegate(ids[0], ids[1]).unwrap();
+    referendum.delegate(ids[1], ids[2]).unwrap();
+
+    assert_eq!(referendum.votes_cast(), 0);
+    assert_eq!(referendum.turnout(), 0.0);
+}
+
+#[test]
+fn delegation_cycle_is_rejected() {
+    let (mut referendum, ids, _) = referendum(3);
+
+    referendum.delegate(ids[0], ids[1]).unwrap();
+    referendum.delegate(ids[1], ids[2]).unwrap();
+
+    assert_eq!(referendum.delegate(ids[2], ids[0]), Err(VoteError::DelegationCycle(ids[2])));
+    assert_eq!(referendum.delegate(ids[0], ids[0]), Err(VoteError::DelegationCycle(ids[0])));
+    assert_eq!(referendum.delegate_of(ids[2]), None);
+    assert_eq!(referendum.delegate_of(ids[0]), Some(ids[1]));
+}
+
+#[test]
+fn direct_vote_overrides_delegation() {
+    let (mut referendum, ids, _) = referendum(3);
+
+    referendum.delegate(ids[0], ids[1]).unwrap();
+    referendum.register_vote_for(ids[1]).unwrap();
+    referendum.register_vote_against(ids[0]).unwrap();
+
+    assert_eq!(referendum.votes_for(), 1);
+    assert_eq!(referendum.votes_against(), 1);
+    assert_eq!(referendum.delegate(ids[0], ids[2]), Err(VoteError::AlreadyVoted(ids[0])));
+}
+
+#[test]
+fn token_holder_votes_only_by_token() {
+    let (mut referendum, ids, _) = referendum(2);
+
+    let token = referendum.issue_token(ids[0]).unwrap();
+
+    assert_eq!(referendum.issue_token(ids[0]), Err(VoteError::TokenIssued(ids[0])));
+    assert_eq!(referendum.register_vote_for(ids[0]), Err(VoteError::TokenIssued(ids[0])));
+    assert_eq!(referendum.delegate(ids[0], ids[1]), Err(VoteError::TokenIssued(ids[0])));
+
+    referendum.register_secret_vote(token, true).unwrap();
+
+    assert_eq!(referendum.register_secret_vote(token, true), Err(VoteError::InvalidToken));
+    assert_eq!(referendum.votes_for(), 1);
+    assert_eq!(referendum.turnout(), 0.5);
+}
+
+#[test]
+fn issuing_token_withdraws_delegation() {
+    let (mut referendum, ids, _) = referendum(2);
+
+    referendum.delegate(ids[0], ids[1]).unwrap();
+    referendum.register_vote_for(ids[1]).unwrap();
+    referendum.issue_token(ids[0]).unwrap();
+
+    assert_eq!(referendum.delegate_of(ids[0]), None);
+    assert_eq!(referendum.votes_for(), 1);
+}
+
+#[test]
+fn direct_voter_is_not_issued_token() {
+    let (mut referendum, ids, _) = referendum(1);
+
+    referendum.register_vote_against(ids[0]).unwrap();
+
+    assert_eq!(referendum.issue_token(ids[0]), Err(VoteError::AlreadyVoted(ids[0])));
+}
+
+#[test]
+fn petitioner_count_rounds_up_within_electorate() {
+    // (electors, ratio, petitioners)
+    let cases = [
+        (1, 0.25, 1), (2, 0.25, 1), (3, 0.25, 1),
+        (1, 0.5, 1), (2, 0.5, 1), (3, 0.5, 2),
+        (1, 1.0, 1), (2, 1.0, 2), (3, 1.0, 3)
+    ];
+
+    for (electors, ratio, petitioners) in cases {
+        assert_eq!(petitioner_count(electors, ratio), Some(petitioners));
+    }
+}
+
+#[test]
+fn petitioner_count_rejects_invalid_ratio() {
+    for ratio in [0.0, -0.5, 1.5, f32::NAN] {
+        assert_eq!(petitioner_count(3, ratio), None);
+    }
+}
+
+#[test]
+fn petition_draws_petitioners_from_electorate() {
+    for size in 1..=3 {
+        let clock = MockClock::new(Utc::now());
+        let (proposal, electors) = proposal(size, &clock);
+        let mut rng = StdRng::seed_from_u64(size);
+
+        let proposal = proposal.into_petition_with_rng(0.5, &mut rng).unwrap_err();
+
+        clock.advance(Duration::days(1));
+
+        let proposal = proposal.into_petition_with_rng(0.0, &mut rng).unwrap_err();
+        let petition = proposal.into_petition_with_rng(0.5, &mut rng).unwrap();
+        let petitioners = petition.voter_ids();
+
+        assert_eq!(petitioners.len(), size.div_ceil(2) as usize);
+        assert!(petitioners.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(petitioners.iter().all(|id| electors.contains(id)));
+    }
+}
+
+#[test]
+fn pipeline_skipping_petition_requires_emergency_motion() {
+    use StageName::*;
+
+    let pipeline = Pipeline::new(vec![Prototype, Proposal, Referendum]).unwrap();
+    let electors = population(3);
+    let motion = Motion::new("motion", "", vec![electors[0]], electors.clone()).unwrap();
+    let anonymous = Motion::new("motion", "", Vec::new(), electors)
+        .unwrap()
+        .with_category(MotionCategory::Emergency);
+
+    assert_eq!(pipeline.validate(&motion), Err(PipelineError::FastTrackUnavailable));
+    assert_eq!(pipeline.validate(&anonymous), Err(PipelineError::FastTrackUnavailable));
+    assert_eq!(pipeline.validate(&motion.with_category(MotionCategory::Emergency)), Ok(()));
+}
+
+#[test]
+fn pipeline_goes_from_prototype_to_referendum() {
+    use StageName::*;
+
+    let pipeline = Pipeline::new(vec![Prototype, Referendum, Passed]).unwrap();
+    let developers = population(3);
+    let motion = Motion::new("motion", "", developers.clone(), developers.clone())
+        .unwrap()
+        .with_category(MotionCategory::Emergency);
+
+    assert_eq!(pipeline.validate(&motion), Ok(()));
+
+    let mut prototype = Procedure::begin(motion);
+    prototype.start_proposal_attempt(Duration::days(1));
+    prototype.register_proposal_vote(developers[0]).unwrap();
+
+    // two thirds of developers must back skipping the debate.
+    let ProcedureAny::Prototype(mut prototype) = ProcedureAny::from(prototype)
+        .advance(&pipeline)
+        .unwrap_err()
+    else {
+        panic!("expected the prototype");
+    };
+
+    prototype.register_proposal_vote(developers[1]).unwrap();
+
+    let procedure = ProcedureAny::from(prototype).advance(&pipeline).unwrap();
+
+    assert_eq!(procedure.stage(), Referendum);
+}
+
+#[test]
+fn pipeline_issues_tokens_of_secret_referendum() {
+    use StageName::*;
+
+    let pipeline = Pipeline::new(vec![Prototype, Proposal, Petition, SecretReferendum, Passed])
+        .unwrap()
+        .with_petitioner_ratio(1.0);
+
+    let (petition, _, clock) = petition(3);
+    let petition = ProcedureAny::from(petition);
+
+    // advancing without the tokens would lose them.
+    let petition = petition.advance(&pipeline).unwrap_err();
+    let (procedure, tokens) = petition.advance_issuing(&pipeline).unwrap();
+
+    let ProcedureAny::SecretReferendum(mut referendum) = procedure else {
+        panic!("expected a secret referendum");
+    };
+
+    assert_eq!(tokens.len(), 3);
+
+    referendum.register_vote(tokens[0].1, true).unwrap();
+    clock.advance(Duration::weeks(1));
+
+    let procedure = ProcedureAny::from(referendum).advance(&pipeline).unwrap();
+
+    assert_eq!(procedure.stage(), Passed);
+}
+
+#[test]
+fn pipeline_requires_district_of_every_elector() {
+    use StageName::*;
+
+    let pipeline = Pipeline::new(vec![Prototype, Proposal, Petition, DistrictedReferendum])
+        .unwrap();
+
+    let (petition, ids, _) = petition(2);
+    let motion = petition.motion().clone();
+
+    let partial = pipeline.clone().with_districts([(ids[0], DistrictId(0))]);
+
+    assert_eq!(partial.validate(&motion), Err(PipelineError::NoDistrict(ids[1])));
+
+    let pipeline = pipeline.with_districts(ids.iter().map(|&id| (id, DistrictId(0))));
+    let procedure = ProcedureAny::from(petition).advance(&pipeline).unwrap();
+
+    assert_eq!(procedure.stage(), DistrictedReferendum);
+}
+
+#[test]
+fn pipeline_rejects_unsupported_orders() {
+    use StageName::*;
+
+    assert_eq!(
+        Pipeline::new(vec![Prototype, Petition]),
+        Err(PipelineError::Unsupported { from: Prototype, to: Petition })
+    );
+    assert_eq!(
+        Pipeline::new(vec![Prototype, Proposal, Proposal, Petition]),
+        Err(PipelineError::Repeated(Proposal))
+    );
+
+    let pipeline = Pipeline::default().with_petitioner_ratio(0.0);
+    let electors = population(1);
+    let motion = Motion::new("motion", "", Vec::new(), electors).unwrap();
+
+    assert_eq!(pipeline.validate(&motion), Err(PipelineError::InvalidRatio));
+}
+
+#[test]
+fn pipeline_draws_each_petition_round() {
+    use StageName::*;
+
+    let pipeline = Pipeline::new(vec![Prototype, Proposal, Petition, Petition, Referendum])
+        .unwrap()
+        .with_petitioner_ratio(1.0);
+
+    let clock = MockClock::new(Utc::now());
+    let electors = population(3);
+    let motion = Motion::new("motion", "", Vec::new(), electors.clone()).unwrap();
+
+    let procedure = ProcedureAny::from(Procedure::begin_with_clock(motion, clock.clone()))
+        .advance(&pipeline)
+        .unwrap();
+
+    clock.advance(Duration::weeks(1));
+
+    let approve = |procedure: ProcedureAny| {
+        let ProcedureAny::Petition(mut petition) = procedure else {
+            panic!("expected a petition");
+        };
+
+        for &id in &electors {
+            petition.register_approval_vote(id).unwrap();
+        }
+
+        petition
+    };
+
+    let first = approve(procedure.advance(&pipeline).unwrap());
+
+    assert_eq!(first.round(), 1);
+
+    let second = approve(ProcedureAny::from(first).advance(&pipeline).unwrap());
+
+    assert_eq!(second.round(), 2);
+
+    // an unapproved round does not proceed, and a reroll keeps its round.
+    let rerolled = second.reroll();
+
+    assert_eq!(rerolled.round(), 2);
+
+    let procedure = ProcedureAny::from(rerolled).advance(&pipeline).unwrap_err();
+    let procedure = approve(procedure);
+
+    let procedure = ProcedureAny::from(procedure).advance(&pipeline).unwrap();
+
+    assert_eq!(procedure.stage(), Referendum);
+}
+
+#[test]
+fn lock_past_last_date_is_rejected_without_voting() {
+    let (mut referendum, ids, _) = referendum(1);
+
+    assert_eq!(
+        referendum.register_vote_for_locked(ids[0], Duration::max_value()),
+        Err(VoteError::InvalidBallot(ids[0]))
+    );
+    assert_eq!(referendum.votes_cast(), 0);
+    assert_eq!(referendum.unlock_date(ids[0]), None);
+
+    referendum.register_vote_for_locked(ids[0], Duration::weeks(2)).unwrap();
+
+    assert_eq!(referendum.votes_for(), 3);
+    assert_eq!(referendum.unlock_date(ids[0]), Some(referendum.end_date() + Duration::weeks(2)));
+}
+
+#[test]
+fn waiting_for_petition_follows_mock_clock() {
+    let clock = MockClock::new(Utc::now());
+    let (proposal, _) = proposal(1, &clock);
+    let advancer = clock.clone();
+    let start = std::time::Instant::now();
+
+    let handle = std::thread::spawn(move || {
+        std::thread::sleep(CLOCK_POLL_INTERVAL);
+        advancer.advance(Duration::days(1));
+    });
+
+    proposal.wait_and_into_petition(1.0).unwrap();
+    handle.join().unwrap();
+
+    // far less than the day of debate.
+    assert!(start.elapsed() < 10 * CLOCK_POLL_INTERVAL);
+}
+
+#[test]
+fn ranked_winner_follows_eliminations() {
+    let (petition, ids, _) = petition(9);
+    let options = ["a", "b", "c"].map(String::from).to_vec();
+    let mut referendum = petition.into_ranked_referendum(options).unwrap();
+
+    // a leads on first preferences, but c is eliminated first and its ballots
+    // carry b to a majority.
+    let ballots = [
+        vec![0], vec![0], vec![0], vec![0],
+        vec![1], vec![1], vec![1, 2],
+        vec![2, 1], vec![2, 1]
+    ];
+
+    for (&id, ranking) in ids.iter().zip(ballots) {
+        referendum.register_ranked_vote(id, ranking).unwrap();
+    }
+
+    assert_eq!(referendum.winner(), Some(1));
+}
+
+#[test]
+fn ranked_tie_has_no_winner() {
+    let (petition, ids, _) = petition(2);
+    let options = ["a", "b"].map(String::from).to_vec();
+    let mut referendum = petition.into_ranked_referendum(options).unwrap();
+
+    referendum.register_ranked_vote(ids[0], vec![0, 1]).unwrap();
+    referendum.register_ranked_vote(ids[1], vec![1, 0]).unwrap();
+
+    assert_eq!(referendum.winner(), None);
+    assert_eq!(
+        referendum.register_ranked_vote(ids[0], vec![1]),
+        Err(VoteError::AlreadyVoted(ids[0]))
+    );
+}
+
+#[test]
+fn unspent_token_keeps_room_in_tally() {
+    let (mut referendum, ids, _) = referendum(3);
+
+    referendum.register_weighted_vote_for(ids[0], u64::MAX - 1).unwrap();
+
+    let token = referendum.issue_token(ids[1]).unwrap();
+
+    assert_eq!(
+        referendum.register_weighted_vote_against(ids[2], 1),
+        Err(VoteError::Overflow(ids[2]))
+    );
+    assert_eq!(referendum.issue_token(ids[2]), Err(VoteError::Overflow(ids[2])));
+
+    referendum.register_secret_vote(token, false).unwrap();
+
+    assert_eq!(referendum.votes_for(), u64::MAX - 1);
+    assert_eq!(referendum.votes_against(), 1);
+
+    referendum.change_vote(ids[0], false).unwrap();
+
+    assert_eq!(referendum.votes_against(), u64::MAX);
+}
+
+#[test]
+fn mismatched_reveal_is_rejected() {
+    use commit_reveal::commitment;
+
+    let (petition, ids, clock) = petition(2);
+    let mut referendum = petition
+        .into_commit_reveal_referendum(Duration::days(1), Duration::days(1))
+        .unwrap();
+
+    referendum.commit(ids[0], commitment(true, b"nonce")).unwrap();
+    referendum.commit(ids[1], commitment(false, b"other")).unwrap();
+
+    assert_eq!(referendum.reveal(ids[0], true, b"nonce"), Err(VoteError::WrongStage));
+
+    clock.advance(Duration::days(1));
+
+    assert_eq!(referendum.reveal(ids[0], false, b"nonce"), Err(VoteError::InvalidBallot(ids[0])));
+    assert_eq!(referendum.reveal(ids[0], true, b"wrong"), Err(VoteError::InvalidBallot(ids[0])));
+
+    referendum.reveal(ids[0], true, b"nonce").unwrap();
+
+    assert_eq!(referendum.votes_for(), 1);
+    assert_eq!(referendum.votes_against(), 0);
+    assert_eq!(referendum.reveal_count(), 1);
+
+    // the unrevealed commitment is discarded.
+    clock.advance(Duration::days(1));
+
+    assert!(referendum.pass().is_ok());
+}
+
+#[test]
+fn overflowing_extension_is_rejected() {
+    let clock = MockClock::new(Utc::now());
+    let electors = population(1);
+    let motion = Motion::new("motion", "", electors.clone(), electors.clone()).unwrap();
+
+    let mut prototype = Procedure::begin_with_clock(motion, clock.clone());
+    prototype.start_proposal_attempt(Duration::days(1));
+    prototype.register_proposal_vote(electors[0]).unwrap();
+
+    let mut proposal = prototype.into_proposal_unchecked(Duration::days(1)).unwrap();
+    let end_date = proposal.end_date();
+
+    assert_eq!(
+        proposal.register_extension_vote(electors[0], Duration::max_value()),
+        Err(VoteError::InvalidBallot(electors[0]))
+    );
+    assert_eq!(proposal.end_date(), end_date);
+
+    proposal.register_extension_vote(electors[0], Duration::days(1)).unwrap();
+
+    assert_eq!(proposal.end_date(), end_date + Duration::days(1));
+    assert_eq!(proposal.extension_count(), 1);
+}
+
+#[test]
+fn overflowing_proposal_attempt_is_not_opened() {
+    let clock = MockClock::new(Utc::now());
+    let electors = population(1);
+    let motion = Motion::new("motion", "", electors.clone(), electors).unwrap();
+
+    let mut prototype = Procedure::begin_with_clock(motion, clock);
+
+    assert!(!prototype.start_proposal_attempt(Duration::max_value()));
+    assert_eq!(prototype.proposal_attempt_dates(), None);
+    assert!(prototype.start_proposal_attempt(Duration::days(1)));
+}
+
+#[test]
+fn districted_referendum_passes_only_once_closed() {
+    let (petition, ids, clock) = petition(3);
+    let first = ids[0];
+
+    let mut referendum = petition
+        .into_districted_referendum(Duration::days(1), |id| DistrictId(u64::from(id == first)))
+        .unwrap();
+
+    for &id in &ids {
+        referendum.register_vote(id, true).unwrap();
+    }
+
+    assert_eq!(referendum.districts_carried(), 2);
+
+    let referendum = referendum.pass().unwrap_err();
+
+    clock.advance(Duration::days(1));
+
+    assert!(referendum.pass().is_ok());
+}
+
+#[test]
+fn districted_stats_sum_districts() {
+    let (petition, ids, _) = petition(3);
+    let first = ids[0];
+
+    let mut referendum = petition
+        .into_districted_referendum(Duration::days(1), |id| DistrictId(u64::from(id == first)))
+        .unwrap();
+
+    referendum.register_vote(ids[0], true).unwrap();
+    referendum.register_vote(ids[1], false).unwrap();
+
+    let stats = referendum.stats();
+
+    assert_eq!((stats.eligible, stats.voters, stats.remaining), (3, 2, 1));
+    assert_eq!((stats.votes_for, stats.votes_against), (1, 1));
+}
+
+#[test]
+fn secret_stats_count_ballots_cast() {
+    let (petition, _, _) = petition(3);
+    let (mut referendum, tokens) = petition.into_secret_referendum(Duration::days(1)).unwrap();
+
+    referendum.register_vote(tokens[0].1, true).unwrap();
+    referendum.register_abstention(tokens[1].1).unwrap();
+
+    let stats = referendum.stats();
+
+    assert_eq!((stats.eligible, stats.voters, stats.remaining), (3, 2, 1));
+    assert_eq!((stats.votes_for, stats.votes_against, stats.abstentions), (1, 0, 1));
+}
+
+#[test]
+fn quadratic_stats_count_votes_bought() {
+    let (petition, ids, _) = petition(3);
+    let mut referendum = petition.into_quadratic_referendum(Duration::days(1), 9).unwrap();
+
+    referendum.register_quadratic(ids[0], Side::For, 3).unwrap();
+    referendum.register_quadratic(ids[1], Side::Against, 1).unwrap();
+
+    let stats = referendum.stats();
+
+    assert_eq!((stats.eligible, stats.voters, stats.remaining), (3, 2, 1));
+    assert_eq!((stats.votes_for, stats.votes_against), (3, 1));
+}
+
+#[test]
+fn quadratic_votes_cost_square_of_total() {
+    let (petition, ids, _) = petition(2);
+    let mut referendum = petition.into_quadratic_referendum(Duration::days(1), 10).unwrap();
+
+    referendum.register_quadratic(ids[0], Side::For, 2).unwrap();
+
+    assert_eq!(referendum.remaining_budget(ids[0]), 6);
+
+    // a third vote costs 9 - 4 = 5 credits, a fourth would cost 16.
+    referendum.register_quadratic(ids[0], Side::For, 1).unwrap();
+
+    assert_eq!(referendum.remaining_budget(ids[0]), 1);
+    assert_eq!(
+        referendum.register_quadratic(ids[0], Side::For, 1),
+        Err(VoteError::InsufficientCredits(ids[0]))
+    );
+    assert_eq!(
+        referendum.register_quadratic(ids[0], Side::Against, 1),
+        Err(VoteError::InvalidBallot(ids[0]))
+    );
+
+    assert_eq!(referendum.votes_for(), 3);
+    assert_eq!(referendum.remaining_budget(ids[1]), 10);
+}
@@ -0,0 +1,28 @@
+//! anonymous ballot tokens for secret voting
+
+use rand::Rng;
+
+/// a one-time credential allowing an elector to cast a ballot without their
+/// identity being recorded, see
+/// [`Procedure::issue_token`](super::Procedure::issue_token)
+///
+/// a token is only valid for the referendum that issued it
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BallotToken([u8; 32]);
+
+impl BallotToken {
+    /// a new token, drawn at random so that nothing derives it from the
+    /// elector it is issued to
+    pub(super) fn random(rng: &mut impl Rng) -> Self {
+        Self(rng.gen())
+    }
+
+    /// the token as raw bytes, for transmission to the elector
+    pub fn to_bytes(self) -> [u8; 32] {
+        self.0
+    }
+
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+}
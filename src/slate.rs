@@ -0,0 +1,162 @@
+//! approval votes over several motions at once
+
+use crate::{Motion, PersonId, VoteError, Clock, SystemClock};
+
+use chrono::Duration;
+
+use std::{collections::HashSet, fmt, sync::Arc};
+
+type DateTime = chrono::DateTime<chrono::Utc>;
+
+/// a referendum over several motions, in which each elector approves any
+/// subset of the motions they are an elector of
+///
+/// electors vote once for the whole slate. once voting closes, each motion
+/// approved by more than a set fraction of its electorate is passed, see
+/// [`into_passed`](Self::into_passed)
+pub struct Slate {
+    motions: Vec<Motion>,
+    end_date: DateTime,
+    have_voted: HashSet<PersonId>,
+    /// approvals of each motion, by index
+    approvals: Vec<u64>,
+    clock: Arc<dyn Clock + Send + Sync>
+}
+
+impl Slate {
+    /// a slate of `motions`, open to votes for `vote_time`
+    pub fn new(motions: Vec<Motion>, vote_time: Duration) -> Self {
+        Self::new_with_clock(motions, vote_time, SystemClock)
+    }
+
+    /// like [`new`](Self::new), with dates taken from `clock`
+    pub fn new_with_clock(
+        mut motions: Vec<Motion>,
+        vote_time: Duration,
+        clock: impl Clock + Send + Sync + 'static
+    ) -> Self {
+        motions.iter_mut().for_each(Motion::sort_electors);
+
+        Self {
+            end_date: clock.now() + vote_time,
+            have_voted: HashSet::new(),
+            approvals: vec![0; motions.len()],
+            motions,
+            clock: Arc::new(clock)
+        }
+    }
+
+    pub fn motions(&self) -> &[Motion] {
+        &self.motions
+    }
+
+    /// the date after which votes are no longer accepted
+    pub fn end_date(&self) -> DateTime {
+        self.end_date
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.clock.now() < self.end_date
+    }
+
+    /// the number of electors who have voted, approving any number of motions
+    pub fn voters(&self) -> u64 {
+        self.have_voted.len() as u64
+    }
+
+    /// approvals of each motion, in the order of [`motions`](Self::motions)
+    pub fn results(&self) -> &[u64] {
+        &self.approvals
+    }
+
+    /// fraction of the electorate of the motion at `index` approving it, from 0
+    /// to 1, or `None` if there is no such motion. 0 if the electorate is empty
+    pub fn approval(&self, index: usize) -> Option<f32> {
+        let electors = self.motions.get(index)?.elector_count();
+
+        Some(match electors {
+            0 => 0.0,
+            n => self.approvals[index] as f32 / n as f32
+        })
+    }
+
+    /// approves the motions at each index of `approved`, on behalf of
+    /// `person_id`, who may approve none
+    ///
+    /// error and does nothing if voting has closed, if `person_id` has already
+    /// voted or is an elector of no motion in the slate, if an index is listed
+    /// twice or designates no motion, or if `person_id` is not an elector of an
+    /// approved motion
+    pub fn register_approvals(
+        &mut self,
+        person_id: PersonId,
+        approved: &[usize]
+    ) -> Result<(), VoteError> {
+        if !self.is_open() {
+            return Err(VoteError::Closed);
+        }
+
+        if !self.motions.iter().any(|m| m.is_elector(person_id)) {
+            return Err(VoteError::NotElector(person_id));
+        }
+
+        if self.have_voted.contains(&person_id) {
+            return Err(VoteError::AlreadyVoted(person_id));
+        }
+
+        let mut seen = HashSet::with_capacity(approved.len());
+
+        for &index in approved {
+            let Some(motion) = self.motions.get(index) else {
+                return Err(VoteError::InvalidBallot(person_id));
+            };
+
+            if !seen.insert(index) {
+                return Err(VoteError::InvalidBallot(person_id));
+            }
+
+            if !motion.is_elector(person_id) {
+                return Err(VoteError::NotElector(person_id));
+            }
+        }
+
+        for &index in approved {
+            self.approvals[index] += 1;
+        }
+
+        self.have_voted.insert(person_id);
+
+        Ok(())
+    }
+
+    /// the motions approved by more than `threshold` of their electorate, in
+    /// order, concluding the vote
+    ///
+    /// returns Err(self) unchanged while voting is open
+    pub fn into_passed(self, threshold: f32) -> Result<Vec<Motion>, Self> {
+        if self.is_open() {
+            return Err(self);
+        }
+
+        let passed = (0..self.motions.len())
+            .map(|index| self.approval(index).is_some_and(|a| a > threshold))
+            .collect::<Vec<_>>();
+
+        Ok(self.motions.into_iter()
+            .zip(passed)
+            .filter_map(|(motion, passed)| passed.then_some(motion))
+            .collect())
+    }
+}
+
+// the clock is left out
+impl fmt::Debug for Slate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Slate")
+            .field("motions", &self.motions)
+            .field("end_date", &self.end_date)
+            .field("have_voted", &self.have_voted)
+            .field("approvals", &self.approvals)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,107 @@
+//! persistence of populations and procedures
+
+use crate::{PersonList, ProcedureId};
+use crate::procedure::ProcedureAny;
+
+use std::{collections::BTreeMap, convert::Infallible, error::Error, ops::Deref};
+
+/// storage of the population and of the procedures of a
+/// [`ProcedureManager`](crate::ProcedureManager)
+///
+/// motions are stored as part of their procedure. loaded values are only read:
+/// a store kept in memory may lend them out by reference, while one backed by
+/// a database may read them into owned values, such as a `Box`. changes are
+/// written back with [`save`](Self::save)
+pub trait Store {
+    /// failure of the backend, such as a lost connection
+    type Error: Error + 'static;
+
+    /// the population as loaded, see [`load_people`](Self::load_people)
+    type PeopleRef<'a>: Deref<Target = PersonList> where Self: 'a;
+
+    /// a procedure as loaded, see [`load`](Self::load)
+    type ProcedureRef<'a>: Deref<Target = ProcedureAny> where Self: 'a;
+
+    fn load_people(&self) -> Result<Option<Self::PeopleRef<'_>>, Self::Error>;
+
+    fn save_people(&mut self, people: PersonList) -> Result<(), Self::Error>;
+
+    fn load(&self, id: ProcedureId) -> Result<Option<Self::ProcedureRef<'_>>, Self::Error>;
+
+    /// stores `procedure` under `id`, replacing any procedure stored under it
+    fn save(&mut self, id: ProcedureId, procedure: ProcedureAny) -> Result<(), Self::Error>;
+
+    fn remove(&mut self, id: ProcedureId) -> Result<Option<ProcedureAny>, Self::Error>;
+
+    /// IDs of all stored procedures, in ascending order
+    fn ids(&self) -> Result<Vec<ProcedureId>, Self::Error>;
+}
+
+/// a store queried without blocking, for stores on high-latency backends
+///
+/// see for instance
+/// [`Procedure::register_vote_for_async`](crate::Procedure::register_vote_for_async)
+#[cfg(feature = "async")]
+pub trait AsyncStore {
+    /// whether `person_id` may vote, on top of the conditions of the procedure
+    fn is_eligible(
+        &self,
+        person_id: crate::PersonId
+    ) -> impl std::future::Future<Output = bool> + Send;
+}
+
+/// a store kept in memory, and lost with it
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    people: Option<PersonList>,
+    procedures: BTreeMap<ProcedureId, ProcedureAny>
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+/// never fails, and lends out values by reference
+impl Store for MemoryStore {
+    type Error = Infallible;
+    type PeopleRef<'a> = &'a PersonList;
+    type ProcedureRef<'a> = &'a ProcedureAny;
+
+    fn load_people(&self) -> Result<Option<&PersonList>, Infallible> {
+        Ok(self.people.as_ref())
+    }
+
+    fn save_people(&mut self, people: PersonList) -> Result<(), Infallible> {
+        self.people = Some(people);
+
+        Ok(())
+    }
+
+    fn load(&self, id: ProcedureId) -> Result<Option<&ProcedureAny>, Infallible> {
+        Ok(self.procedures.get(&id))
+    }
+
+    fn save(&mut self, id: ProcedureId, procedure: ProcedureAny) -> Result<(), Infallible> {
+        self.procedures.insert(id, procedure);
+
+        Ok(())
+    }
+
+    fn remove(&mut self, id: ProcedureId) -> Result<Option<ProcedureAny>, Infallible> {
+        Ok(self.procedures.remove(&id))
+    }
+
+    fn ids(&self) -> Result<Vec<ProcedureId>, Infallible> {
+        Ok(self.procedures.keys().copied().collect())
+    }
+}
+
+/// resolves immediately. without a population, everyone is eligible
+#[cfg(feature = "async")]
+impl AsyncStore for MemoryStore {
+    async fn is_eligible(&self, person_id: crate::PersonId) -> bool {
+        self.people.as_ref().is_none_or(|people| people.contains(person_id))
+    }
+}
@@ -0,0 +1,100 @@
+//! procedures shared between threads
+
+use crate::{PersonId, VoteError};
+use crate::procedure::{Procedure, ProcedureStage, Prototype, Petition, Referendum};
+
+use std::sync::{Mutex, MutexGuard};
+
+/// a procedure whose votes may be registered concurrently, through a shared
+/// reference, for instance from behind an `Arc`
+///
+/// the whole procedure is guarded by a single lock, held for the duration of
+/// each operation. operations are therefore linearizable: each takes effect
+/// at once, in some order consistent with their real-time order, and tallies
+/// read at any time reflect exactly the votes registered before
+///
+/// a panic while the lock is held, such as in an observer or in the closure
+/// passed to [`with`](Self::with), may leave the procedure partly changed. the
+/// procedure is then poisoned, and every later operation panics in turn
+#[derive(Debug)]
+pub struct SyncProcedure<St: ProcedureStage> {
+    inner: Mutex<Procedure<St>>
+}
+
+impl<St: ProcedureStage> SyncProcedure<St> {
+    pub fn new(procedure: Procedure<St>) -> Self {
+        Self { inner: Mutex::new(procedure) }
+    }
+
+    /// runs `f` with exclusive access to the procedure, for operations not
+    /// exposed by the wrapper
+    pub fn with<R>(&self, f: impl FnOnce(&mut Procedure<St>) -> R) -> R {
+        f(&mut self.lock())
+    }
+
+    /// the procedure, for instance to transition it once voting is over
+    ///
+    /// panics if the procedure is poisoned
+    pub fn into_inner(self) -> Procedure<St> {
+        self.inner.into_inner().expect(POISONED)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Procedure<St>> {
+        self.inner.lock().expect(POISONED)
+    }
+}
+
+const POISONED: &str = "procedure poisoned by a panic while it was locked";
+
+impl SyncProcedure<Prototype> {
+    /// see [`Procedure::register_proposal_vote`]
+    pub fn register_proposal_vote(&self, person_id: PersonId) -> Result<(), VoteError> {
+        self.lock().register_proposal_vote(person_id)
+    }
+
+    pub fn proposal_votes(&self) -> u64 {
+        self.lock().proposal_votes()
+    }
+}
+
+impl SyncProcedure<Petition> {
+    /// see [`Procedure::register_approval_vote`]
+    pub fn register_approval_vote(&self, person_id: PersonId) -> Result<(), VoteError> {
+        self.lock().register_approval_vote(person_id)
+    }
+
+    pub fn votes_for(&self) -> u64 {
+        self.lock().votes_for()
+    }
+}
+
+impl SyncProcedure<Referendum> {
+    /// see [`Procedure::register_vote_for`]
+    pub fn register_vote_for(&self, person_id: PersonId) -> Result<(), VoteError> {
+        self.lock().register_vote_for(person_id)
+    }
+
+    /// see [`Procedure::register_vote_against`]
+    pub fn register_vote_against(&self, person_id: PersonId) -> Result<(), VoteError> {
+        self.lock().register_vote_against(person_id)
+    }
+
+    /// see [`Procedure::register_abstention`]
+    pub fn register_abstention(&self, person_id: PersonId) -> Result<(), VoteError> {
+        self.lock().register_abstention(person_id)
+    }
+
+    pub fn votes_for(&self) -> u64 {
+        self.lock().votes_for()
+    }
+
+    pub fn votes_against(&self) -> u64 {
+        self.lock().votes_against()
+    }
+}
+
+impl<St: ProcedureStage> From<Procedure<St>> for SyncProcedure<St> {
+    fn from(procedure: Procedure<St>) -> Self {
+        Self::new(procedure)
+    }
+}